@@ -48,6 +48,7 @@ fn main() {
 
     let mut window = video_subsystem.window("rust-sdl2 demo: Video", 800, 600)
         .position_centered()
+        .resizable()
         .opengl()
         .build()
         .unwrap();
@@ -66,7 +67,7 @@ fn main() {
 
     let screen = Screen::new("test screen".to_string(), "Test screen".to_string(), &mut window);
     //let font = vg.create_font_mem("SANS_FONT", SANS_FONT).unwrap();
-    let font = screen.borrow().nanovg_context().create_font("Roboto-Regular.ttf", "Roboto-Regular.ttf").unwrap();
+    let font = screen.borrow().nanovg_context().unwrap().create_font("Roboto-Regular.ttf", "Roboto-Regular.ttf").unwrap();
     let label = Label::new("test label".to_string(), "This is a label".to_string(), "Roboto-Regular.ttf".to_string(), Some(font));
     //let label = Label::new_create_font("test label".to_string(), "This is a label".to_string(), "Roboto-Regular.ttf".to_string(), screen.borrow().nanovg_context());
     label.borrow_mut().set_size((200, 20));
@@ -75,30 +76,40 @@ fn main() {
     label.borrow_mut().set_color((255, 255, 255, 255));
     push_child(screen.clone(), label.clone());
 
+    screen.borrow_mut().set_background((0.0, 0.0, 0.0));
+
     let mut posx = 0;
     let mut posy = 0;
     'running: loop {
-        for event in event_pump.poll_iter() {
+        let mut events: Vec<Event> = event_pump.poll_iter().collect();
+
+        // sleep until the next event when there is nothing new to draw
+        if events.is_empty() && !screen.borrow().needs_redraw() {
+            let timeout = screen.borrow().redraw_timeout();
+            match timeout {
+                Some(val) => {
+                    if let Some(event) = event_pump.wait_event_timeout(val) {
+                        events.push(event);
+                    }
+                },
+                None => events.push(event_pump.wait_event())
+            }
+        }
+
+        for event in events {
             match event {
                 Event::Quit {..} | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
                     break 'running
                 },
-                _ => {}
+                _ => {
+                    Screen::handle_event(screen.clone(), &event);
+                }
             }
         }
 
-        unsafe {gl::Viewport(0, 0, 800, 600)};
-        unsafe {gl::ClearColor(0.0, 0.0, 0.0, 0.0)};
-        unsafe {gl::Clear(gl::COLOR_BUFFER_BIT|gl::DEPTH_BUFFER_BIT|gl::STENCIL_BUFFER_BIT)};
-        unsafe {gl::Enable(gl::BLEND)};
-        unsafe {gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA)};
-        unsafe {gl::Enable(gl::CULL_FACE)};
-        unsafe {gl::Disable(gl::DEPTH_TEST)};
-
-
-        screen.borrow().draw_widgets();
-
-        window.gl_swap_window();
+        if screen.borrow().draw_all() {
+            window.gl_swap_window();
+        }
 
         posx += 1;
         posy += 1;
@@ -110,5 +121,6 @@ fn main() {
         }
 
         label.borrow_mut().set_pos((posx, posy));
+        screen.borrow().request_redraw();
     }
 }
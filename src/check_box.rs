@@ -0,0 +1,413 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use widget::{Widget, WidgetObj};
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+// check mark glyph of the icons font
+const ICON_CHECK: &'static str = "\u{2714}";
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum CheckState {
+    Unchecked,
+    Checked,
+    // neither, e.g. for a box summarizing several others; the user can only
+    // toggle it to checked
+    Indeterminate
+}
+
+pub struct CheckBox {
+    widget: WidgetObj,
+    caption: String,
+    state: Cell<CheckState>,
+    pushed: Cell<bool>,
+    on_change: Option<Box<Fn(bool)>>
+}
+
+impl Widget for CheckBox {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        let fixed = self.widget.fixed_size;
+        if fixed.0 > 0 && fixed.1 > 0 {
+            return fixed
+        }
+
+        let font_size = self.widget.font_size() as f32;
+        nanovg_context.font_size(font_size);
+        nanovg_context.font_face(&self.widget.font());
+        nanovg_context.text_align(nanovg::LEFT | nanovg::MIDDLE);
+        let text_width = nanovg_context.text_bounds(0.0, 0.0, &self.caption, None);
+
+        (if fixed.0 > 0 { fixed.0 } else { (text_width + 1.8 * font_size) as u32 },
+         if fixed.1 > 0 { fixed.1 } else { (font_size * 1.3) as u32 })
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        self.widget.draw(nanovg_context);
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let h = self.widget.size.1 as f32;
+        let font_size = self.widget.font_size() as f32;
+        let enabled = self.widget.enabled_recursive();
+
+        nanovg_context.font_size(font_size);
+        nanovg_context.font_face(&self.widget.font());
+        nanovg_context.text_align(nanovg::LEFT | nanovg::MIDDLE);
+        let text_color = if enabled { theme.text_color() } else { theme.disabled_text_color() };
+        nanovg_context.fill_color(text_color.to_nanovg_color());
+        nanovg_context.text(1.6 * font_size, h / 2.0, &self.caption);
+
+        // the box is a square as high as the widget
+        let inner = if self.pushed.get() { nanovg::Color::rgba(0, 0, 0, 100) } else { nanovg::Color::rgba(0, 0, 0, 32) };
+        let background = nanovg_context.box_gradient(1.5, 1.5, h - 2.0, h - 2.0, 3.0, 3.0,
+            inner, nanovg::Color::rgba(0, 0, 0, 180));
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(1.0, 1.0, h - 2.0, h - 2.0, 3.0);
+        nanovg_context.fill_paint(background);
+        nanovg_context.fill();
+
+        let icon_color = if enabled { theme.icon_color() } else { theme.disabled_text_color() };
+        match self.state.get() {
+            CheckState::Checked => {
+                nanovg_context.font_size(h);
+                nanovg_context.font_face("icons");
+                nanovg_context.fill_color(icon_color.to_nanovg_color());
+                nanovg_context.text_align(nanovg::CENTER | nanovg::MIDDLE);
+                nanovg_context.text(h / 2.0 + 1.0, h / 2.0, ICON_CHECK);
+            },
+            CheckState::Indeterminate => {
+                nanovg_context.begin_path();
+                nanovg_context.rect(h * 0.25, h / 2.0 - 1.5, h * 0.5, 3.0);
+                nanovg_context.fill_color(icon_color.to_nanovg_color());
+                nanovg_context.fill();
+            },
+            CheckState::Unchecked => {}
+        }
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    // events
+
+    // Toggles when the button is released over the box, so a press can still be
+    // cancelled by moving away.
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        if button != Mouse::Left || !self.widget.enabled_recursive() {
+            return self.widget.mouse_button_event(p, button, down, mods)
+        }
+
+        if down {
+            if !self.widget.focused {
+                self.request_focus();
+            }
+            self.pushed.set(true);
+        } else if self.pushed.get() {
+            self.pushed.set(false);
+            if self.contains(p) {
+                self.toggle();
+            }
+        }
+
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_drag_event(p, rel, button, mods)
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        self.widget.scroll_event(p, rel)
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        if self.widget.focused && self.widget.enabled_recursive() && scancode == Some(Scancode::Space) {
+            if pressed {
+                self.toggle();
+            }
+            return true
+        }
+
+        self.widget.keyboard_event(key, scancode, pressed, mods)
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl CheckBox {
+    pub fn new(id: String, caption: String) -> Rc<RefCell<CheckBox>> {
+        Rc::new(RefCell::new(CheckBox {
+            widget: WidgetObj::new(id),
+            caption: caption,
+            state: Cell::new(CheckState::Unchecked),
+            pushed: Cell::new(false),
+            on_change: None
+        }))
+    }
+
+    impl_get_set_clone!(caption, String);
+
+    pub fn checked(&self) -> bool {
+        self.state.get() == CheckState::Checked
+    }
+
+    /// Changes the state without calling on_change.
+    pub fn set_checked(&mut self, checked: bool) {
+        self.state.set(if checked { CheckState::Checked } else { CheckState::Unchecked });
+    }
+
+    pub fn state(&self) -> CheckState {
+        self.state.get()
+    }
+
+    pub fn set_state(&mut self, state: CheckState) {
+        self.state.set(state);
+    }
+
+    pub fn pushed(&self) -> bool {
+        self.pushed.get()
+    }
+
+    /// Called with the new checked state whenever the user toggles the box.
+    pub fn set_on_change(&mut self, callback: Option<Box<Fn(bool)>>) {
+        self.on_change = callback;
+    }
+
+    fn toggle(&self) {
+        let checked = self.state.get() != CheckState::Checked;
+        self.state.set(if checked { CheckState::Checked } else { CheckState::Unchecked });
+
+        if let Some(ref val) = self.on_change {
+            val(checked);
+        }
+    }
+}
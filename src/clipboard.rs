@@ -0,0 +1,73 @@
+extern crate sdl2_sys;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+
+// Text widgets reach the system clipboard through the Screen's Clipboard, so copy
+// and paste can be exercised without a display by swapping in a MemoryClipboard.
+pub trait Clipboard {
+    fn text(&self) -> Option<String>;
+    fn set_text(&mut self, text: &str);
+}
+
+pub struct SdlClipboard;
+
+impl SdlClipboard {
+    // Requires an initialized SDL video subsystem.
+    pub fn new() -> SdlClipboard {
+        SdlClipboard
+    }
+}
+
+impl Clipboard for SdlClipboard {
+    fn text(&self) -> Option<String> {
+        unsafe {
+            if sdl2_sys::clipboard::SDL_HasClipboardText() == 0 {
+                return None
+            }
+
+            let raw = sdl2_sys::clipboard::SDL_GetClipboardText();
+            if raw.is_null() {
+                return None
+            }
+
+            let text = CStr::from_ptr(raw).to_string_lossy().into_owned();
+            sdl2_sys::stdinc::SDL_free(raw as *mut c_void);
+            Some(text)
+        }
+    }
+
+    fn set_text(&mut self, text: &str) {
+        // SDL takes a C string, so anything after an interior nul is dropped
+        let text = text.split('\0').next().unwrap_or("");
+        let text = CString::new(text).unwrap();
+        unsafe {
+            sdl2_sys::clipboard::SDL_SetClipboardText(text.as_ptr());
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MemoryClipboard {
+    text: Rc<RefCell<Option<String>>>
+}
+
+impl MemoryClipboard {
+    pub fn new() -> MemoryClipboard {
+        MemoryClipboard {
+            text: Rc::new(RefCell::new(None))
+        }
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn text(&self) -> Option<String> {
+        self.text.borrow().clone()
+    }
+
+    fn set_text(&mut self, text: &str) {
+        *self.text.borrow_mut() = Some(text.to_string());
+    }
+}
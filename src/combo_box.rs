@@ -0,0 +1,924 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use std::cmp::{max, min};
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use widget::{Widget, WidgetObj};
+use widget_container::{draw_origin, show_overlay};
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+// the list scrolls when there are more items than this
+const MAX_VISIBLE_ROWS: usize = 8;
+
+const LIST_PADDING: u32 = 4;
+const TEXT_PADDING: f32 = 8.0;
+const ARROW_WIDTH: f32 = 20.0;
+
+// State shared by the combo box and its list.
+struct ComboState {
+    items: RefCell<Vec<(String, String)>>,
+    selected: Cell<usize>,
+    open: Cell<bool>,
+    scroll: Cell<usize>,
+    hovered: Cell<Option<usize>>,
+    on_change: RefCell<Option<Box<Fn(usize)>>>
+}
+
+impl ComboState {
+    fn len(&self) -> usize {
+        self.items.borrow().len()
+    }
+
+    // Selects `index` as the user did, calling on_change when it changed.
+    fn select(&self, index: usize) {
+        if index >= self.len() || index == self.selected.get() {
+            return
+        }
+
+        self.selected.set(index);
+        self.scroll_to(index);
+        if let Some(ref val) = *self.on_change.borrow() {
+            val(index);
+        }
+    }
+
+    fn scroll_to(&self, index: usize) {
+        let scroll = self.scroll.get();
+        if index < scroll {
+            self.scroll.set(index);
+        } else if index >= scroll + MAX_VISIBLE_ROWS {
+            self.scroll.set(index + 1 - MAX_VISIBLE_ROWS);
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.len().saturating_sub(MAX_VISIBLE_ROWS)
+    }
+}
+
+/// A button showing the short text of the selected item. Clicking it opens a
+/// list of the item descriptions above all windows, attached below the button.
+pub struct ComboBox {
+    widget: WidgetObj,
+    state: Rc<ComboState>,
+    list: Rc<RefCell<ComboList>>
+}
+
+impl Widget for ComboBox {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        let fixed = self.widget.fixed_size;
+        if fixed.0 > 0 && fixed.1 > 0 {
+            return fixed
+        }
+
+        let font_size = self.widget.font_size() as f32;
+        nanovg_context.font_size(font_size);
+        nanovg_context.font_face(&self.widget.font());
+        let mut text_width = 0.0;
+        for &(ref short, _) in self.state.items.borrow().iter() {
+            let width = nanovg_context.text_bounds(0.0, 0.0, short, None);
+            if width > text_width {
+                text_width = width;
+            }
+        }
+
+        (if fixed.0 > 0 { fixed.0 } else { (text_width + 2.0 * TEXT_PADDING + ARROW_WIDTH) as u32 },
+         if fixed.1 > 0 { fixed.1 } else { (font_size + 10.0) as u32 })
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        self.widget.draw(nanovg_context);
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let (w, h) = (self.widget.size.0 as f32, self.widget.size.1 as f32);
+        let corner_radius = theme.button_corner_radius() as f32;
+        let enabled = self.widget.enabled_recursive();
+
+        let (gradient_top, gradient_bot) = if self.state.open.get() {
+            (theme.button_gradient_top_pushed(), theme.button_gradient_bot_pushed())
+        } else if self.widget.mouse_focus && enabled {
+            (theme.button_gradient_top_focused(), theme.button_gradient_bot_focused())
+        } else {
+            (theme.button_gradient_top_unfocused(), theme.button_gradient_bot_unfocused())
+        };
+
+        let background = nanovg_context.linear_gradient(0.0, 0.0, 0.0, h,
+            gradient_top.to_nanovg_color(), gradient_bot.to_nanovg_color());
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(1.0, 1.0, w - 2.0, h - 2.0, corner_radius - 1.0);
+        nanovg_context.fill_paint(background);
+        nanovg_context.fill();
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(0.5, 1.5, w - 1.0, h - 2.0, corner_radius);
+        nanovg_context.stroke_color(theme.border_light().to_nanovg_color());
+        nanovg_context.stroke();
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(0.5, 0.5, w - 1.0, h - 2.0, corner_radius);
+        nanovg_context.stroke_color(theme.border_dark().to_nanovg_color());
+        nanovg_context.stroke();
+
+        let text_color = if enabled { theme.text_color() } else { theme.disabled_text_color() };
+        nanovg_context.font_size(self.widget.font_size() as f32);
+        nanovg_context.font_face(&self.widget.font());
+        nanovg_context.text_align(nanovg::LEFT | nanovg::MIDDLE);
+        nanovg_context.fill_color(text_color.to_nanovg_color());
+        nanovg_context.text(TEXT_PADDING, h / 2.0, &self.caption());
+
+        let arrow_color = if enabled { theme.icon_color() } else { theme.disabled_text_color() };
+        let arrow_x = w - ARROW_WIDTH / 2.0 - 2.0;
+        nanovg_context.begin_path();
+        nanovg_context.move_to(arrow_x - 4.0, h / 2.0 - 2.0);
+        nanovg_context.line_to(arrow_x + 4.0, h / 2.0 - 2.0);
+        nanovg_context.line_to(arrow_x, h / 2.0 + 3.0);
+        nanovg_context.close_path();
+        nanovg_context.fill_color(arrow_color.to_nanovg_color());
+        nanovg_context.fill();
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    // events
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        if button != Mouse::Left || !self.widget.enabled_recursive() {
+            return self.widget.mouse_button_event(p, button, down, mods)
+        }
+
+        if down {
+            if !self.widget.focused {
+                self.request_focus();
+            }
+            self.set_popup_visible(!self.state.open.get());
+        }
+
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_drag_event(p, rel, button, mods)
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    // The wheel steps through the items without opening the list.
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        if rel.1 == 0 || self.state.len() == 0 || !self.widget.enabled_recursive() {
+            return self.widget.scroll_event(p, rel)
+        }
+
+        self.step_selection(-rel.1.signum());
+        true
+    }
+
+    // Losing the focus closes the list, unless the focus moved to the list itself.
+    fn focus_event(&mut self, focused: bool) -> bool {
+        if !focused {
+            self.set_popup_visible(false);
+        }
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        if !self.widget.focused || !self.widget.enabled_recursive() {
+            return self.widget.keyboard_event(key, scancode, pressed, mods)
+        }
+
+        match scancode {
+            Some(Scancode::Up) => {
+                if pressed {
+                    self.step_selection(-1);
+                }
+                true
+            },
+            Some(Scancode::Down) => {
+                if pressed {
+                    self.step_selection(1);
+                }
+                true
+            },
+            Some(Scancode::Return) | Some(Scancode::KpEnter) | Some(Scancode::Space) => {
+                if pressed {
+                    self.set_popup_visible(!self.state.open.get());
+                }
+                true
+            },
+            Some(Scancode::Escape) if self.state.open.get() => {
+                if pressed {
+                    self.set_popup_visible(false);
+                }
+                true
+            },
+            _ => self.widget.keyboard_event(key, scancode, pressed, mods)
+        }
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl ComboBox {
+    pub fn new(id: String) -> Rc<RefCell<ComboBox>> {
+        let state = Rc::new(ComboState {
+            items: RefCell::new(Vec::new()),
+            selected: Cell::new(0),
+            open: Cell::new(false),
+            scroll: Cell::new(0),
+            hovered: Cell::new(None),
+            on_change: RefCell::new(None)
+        });
+
+        let list = Rc::new(RefCell::new(ComboList {
+            widget: WidgetObj::new(format!("{}.list", id)),
+            state: state.clone()
+        }));
+
+        Rc::new(RefCell::new(ComboBox {
+            widget: WidgetObj::new(id),
+            state: state,
+            list: list
+        }))
+    }
+
+    /// The items as (short text, description) pairs. The button shows the short
+    /// text of the selected item, the list shows the descriptions.
+    pub fn items(&self) -> Vec<(String, String)> {
+        self.state.items.borrow().clone()
+    }
+
+    /// Replaces the items; the selection is kept if it is still valid.
+    pub fn set_items(&mut self, items: Vec<(String, String)>) {
+        let len = items.len();
+        *self.state.items.borrow_mut() = items;
+        if self.state.selected.get() >= len {
+            self.state.selected.set(0);
+        }
+        self.state.scroll.set(0);
+        self.state.hovered.set(None);
+        let selected = self.state.selected.get();
+        self.state.scroll_to(selected);
+    }
+
+    /// Uses every string as both the short text and the description.
+    pub fn set_item_strings(&mut self, items: Vec<String>) {
+        self.set_items(items.into_iter().map(|x| (x.clone(), x)).collect());
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.state.selected.get()
+    }
+
+    /// Selects an item without calling on_change. Indices past the end are ignored.
+    pub fn set_selected_index(&mut self, index: usize) {
+        if index < self.state.len() {
+            self.state.selected.set(index);
+            self.state.scroll_to(index);
+        }
+    }
+
+    /// The short text of the selected item, or an empty string without items.
+    pub fn caption(&self) -> String {
+        match self.state.items.borrow().get(self.state.selected.get()) {
+            Some(val) => val.0.clone(),
+            None => String::new()
+        }
+    }
+
+    /// Called with the index of the item the user selected.
+    pub fn set_on_change(&mut self, callback: Option<Box<Fn(usize)>>) {
+        *self.state.on_change.borrow_mut() = callback;
+    }
+
+    /// The list shown while the combo box is open.
+    pub fn popup(&self) -> Rc<RefCell<Widget>> {
+        self.list.clone()
+    }
+
+    pub fn popup_visible(&self) -> bool {
+        self.state.open.get()
+    }
+
+    /// Opens the list below the combo box, or closes it.
+    pub fn set_popup_visible(&self, visible: bool) {
+        if visible == self.state.open.get() {
+            return
+        }
+
+        if !visible {
+            self.state.open.set(false);
+            self.state.hovered.set(None);
+            return
+        }
+
+        if self.state.len() == 0 {
+            return
+        }
+
+        // placed where the combo box is shown, which differs from its absolute
+        // position inside a scrolled panel
+        let (x, y) = draw_origin(self);
+        let (w, h) = self.widget.size;
+        let font_size = self.widget.font_size();
+        let rows = min(self.state.len(), MAX_VISIBLE_ROWS) as u32;
+        {
+            let mut list = self.list.borrow_mut();
+            list.set_theme(self.widget.theme.clone());
+            list.set_font_size(Some(font_size));
+            list.set_pos((max(x, 0) as u32, max(y + h as i32, 0) as u32));
+            list.set_size((w, rows * row_height(font_size) + 2 * LIST_PADDING));
+        }
+
+        let selected = self.state.selected.get();
+        self.state.scroll_to(selected);
+        self.state.open.set(true);
+        show_overlay(self, self.list.clone());
+    }
+
+    fn step_selection(&self, delta: i32) {
+        let selected = self.state.selected.get() as i32 + delta;
+        if selected >= 0 {
+            self.state.select(selected as usize);
+        }
+    }
+}
+
+fn row_height(font_size: u32) -> u32 {
+    font_size + 8
+}
+
+// The list of a combo box. It is visible exactly while the combo box is open.
+struct ComboList {
+    widget: WidgetObj,
+    state: Rc<ComboState>
+}
+
+impl Widget for ComboList {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.state.open.get()
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.state.open.set(visible);
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        self.widget.preferred_size(nanovg_context)
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        if !self.state.open.get() {
+            return
+        }
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let (w, h) = (self.widget.size.0 as f32, self.widget.size.1 as f32);
+        let corner_radius = theme.window_corner_radius() as f32;
+        let font_size = self.widget.font_size();
+        let row_h = row_height(font_size) as f32;
+        let padding = LIST_PADDING as f32;
+
+        let shadow = nanovg_context.box_gradient(0.0, 2.0, w, h, corner_radius * 2.0, 10.0,
+            theme.dropshadow().to_nanovg_color(), theme.transparent().to_nanovg_color());
+        nanovg_context.begin_path();
+        nanovg_context.rect(-10.0, -10.0, w + 20.0, h + 20.0);
+        nanovg_context.fill_paint(shadow);
+        nanovg_context.fill();
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(0.0, 0.0, w, h, corner_radius);
+        nanovg_context.fill_color(theme.window_popup().to_nanovg_color());
+        nanovg_context.fill();
+        nanovg_context.stroke_color(theme.border_dark().to_nanovg_color());
+        nanovg_context.stroke();
+
+        let items = self.state.items.borrow();
+        let scroll = self.state.scroll.get();
+        let last = min(items.len(), scroll + MAX_VISIBLE_ROWS);
+        let scrollbar = items.len() > MAX_VISIBLE_ROWS;
+        let row_w = if scrollbar { w - 2.0 * padding - 6.0 } else { w - 2.0 * padding };
+
+        nanovg_context.font_size(font_size as f32);
+        nanovg_context.font_face(&self.widget.font());
+        nanovg_context.text_align(nanovg::LEFT | nanovg::MIDDLE);
+        for index in scroll..last {
+            let row_y = padding + (index - scroll) as f32 * row_h;
+            if index == self.state.selected.get() || self.state.hovered.get() == Some(index) {
+                nanovg_context.begin_path();
+                nanovg_context.rounded_rect(padding, row_y, row_w, row_h, 2.0);
+                if index == self.state.selected.get() {
+                    nanovg_context.fill_color(theme.text_selection_color().to_nanovg_color());
+                } else {
+                    nanovg_context.fill_color(nanovg::Color::rgba(255, 255, 255, 20));
+                }
+                nanovg_context.fill();
+            }
+
+            nanovg_context.fill_color(theme.text_color().to_nanovg_color());
+            nanovg_context.text(padding + TEXT_PADDING, row_y + row_h / 2.0, &items[index].1);
+        }
+
+        if scrollbar {
+            let track_h = h - 2.0 * padding;
+            let bar_h = (track_h * MAX_VISIBLE_ROWS as f32 / items.len() as f32).max(8.0);
+            let bar_y = padding + (track_h - bar_h) * scroll as f32 / self.state.max_scroll() as f32;
+            nanovg_context.begin_path();
+            nanovg_context.rounded_rect(w - padding - 4.0, bar_y, 4.0, bar_h, 2.0);
+            nanovg_context.fill_color(theme.scrollbar_thumb_inner().to_nanovg_color());
+            nanovg_context.fill();
+        }
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.state.open.get() {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    // keeps the drop shadow
+    fn clip_to_bounds(&self) -> bool {
+        false
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    // events
+
+    // Clicking an item selects it and closes the list.
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, _: SDL_Keymod) -> bool {
+        if button == Mouse::Left && down {
+            if let Some(index) = self.row_at(p) {
+                self.state.select(index);
+                self.state.open.set(false);
+                self.state.hovered.set(None);
+            }
+        }
+
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), _: (i32, i32), _: Mouse, _: SDL_Keymod) -> bool {
+        self.state.hovered.set(self.row_at(p));
+        true
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_drag_event(p, rel, button, mods)
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        if !enter {
+            self.state.hovered.set(None);
+        }
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, _: (u32, u32), rel: (i32, i32)) -> bool {
+        let scroll = self.state.scroll.get() as i32 - rel.1;
+        let max_scroll = self.state.max_scroll() as i32;
+        self.state.scroll.set(if scroll < 0 { 0 } else if scroll > max_scroll { max_scroll as usize } else { scroll as usize });
+        true
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        self.widget.keyboard_event(key, scancode, pressed, mods)
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl ComboList {
+    // `p` is in parent coordinates, like the mouse events.
+    fn row_at(&self, p: (u32, u32)) -> Option<usize> {
+        if !self.contains(p) {
+            return None
+        }
+
+        let local_y = p.1 - self.widget.pos.1;
+        if local_y < LIST_PADDING {
+            return None
+        }
+
+        let index = ((local_y - LIST_PADDING) / row_height(self.widget.font_size())) as usize + self.state.scroll.get();
+        let last = min(self.state.len(), self.state.scroll.get() + MAX_VISIBLE_ROWS);
+        if index < last { Some(index) } else { None }
+    }
+}
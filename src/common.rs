@@ -1,5 +1,7 @@
+extern crate nanovg;
+
 use std::fmt;
-#[derive(PartialEq, Copy, Clone)]
+#[derive(PartialEq, Copy, Clone, Debug)]
 pub enum Cursor {
     Arrow = 0,
     IBeam,
@@ -54,6 +56,10 @@ impl Color {
             a: alpha,
         }
     }
+
+    pub fn to_nanovg_color(&self) -> nanovg::Color {
+        nanovg::Color::rgba(self.r as u8, self.g as u8, self.b as u8, self.a as u8)
+    }
 }
 
 #[macro_export]
@@ -0,0 +1,109 @@
+extern crate sdl2;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use self::sdl2::mouse::SystemCursor;
+use common::Cursor;
+
+// Screen only talks to the OS cursor through a CursorBackend, so cursor handling
+// can be exercised without a display by swapping in a RecordingCursorBackend.
+pub trait CursorBackend {
+    fn set_cursor(&mut self, cursor: Cursor);
+}
+
+// Creates the SDL system cursors the first time one is set. Cursors SDL cannot
+// create, e.g. because its video subsystem is not initialized, are skipped, so
+// setting them does nothing.
+pub struct SdlCursorBackend {
+    cursors: Option<Vec<Option<sdl2::mouse::Cursor>>>
+}
+
+impl SdlCursorBackend {
+    pub fn new() -> SdlCursorBackend {
+        SdlCursorBackend {
+            cursors: None
+        }
+    }
+
+    fn load_cursors() -> Vec<Option<sdl2::mouse::Cursor>> {
+        let system_cursors = [
+            SystemCursor::Arrow,
+            SystemCursor::IBeam,
+            SystemCursor::Crosshair,
+            SystemCursor::Hand,
+            SystemCursor::SizeWE,
+            SystemCursor::SizeNS
+        ];
+
+        system_cursors.iter().map(|x| sdl2::mouse::Cursor::from_system(*x).ok()).collect()
+    }
+}
+
+impl CursorBackend for SdlCursorBackend {
+    fn set_cursor(&mut self, cursor: Cursor) {
+        if cursor == Cursor::CursorCount {
+            return
+        }
+
+        if self.cursors.is_none() {
+            self.cursors = Some(SdlCursorBackend::load_cursors());
+        }
+
+        if let Some(ref val) = self.cursors.as_ref().unwrap()[cursor as usize] {
+            val.set();
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RecordingCursorBackend {
+    requests: Rc<RefCell<Vec<Cursor>>>
+}
+
+impl RecordingCursorBackend {
+    pub fn new() -> RecordingCursorBackend {
+        RecordingCursorBackend {
+            requests: Rc::new(RefCell::new(Vec::new()))
+        }
+    }
+
+    pub fn last_cursor(&self) -> Option<Cursor> {
+        self.requests.borrow().last().cloned()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.requests.borrow().len()
+    }
+}
+
+impl CursorBackend for RecordingCursorBackend {
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.requests.borrow_mut().push(cursor);
+    }
+}
+
+pub struct CursorManager {
+    backend: Box<CursorBackend>,
+    current: Option<Cursor>
+}
+
+impl CursorManager {
+    pub fn new(backend: Box<CursorBackend>) -> CursorManager {
+        CursorManager {
+            backend: backend,
+            current: None
+        }
+    }
+
+    // Only forwards the cursor to the backend when it differs from the current one.
+    pub fn update(&mut self, cursor: Cursor) {
+        if self.current != Some(cursor) {
+            self.current = Some(cursor);
+            self.backend.set_cursor(cursor);
+        }
+    }
+
+    pub fn current(&self) -> Option<Cursor> {
+        self.current
+    }
+}
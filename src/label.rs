@@ -8,7 +8,9 @@ use self::sdl2::keyboard::{Mod, Scancode};
 use self::sdl2::mouse::Mouse;
 use self::sdl2_sys::keycode::SDL_Keymod;
 use common::Cursor;
+use shortcut::{KMOD_CTRL, KMOD_GUI};
 use widget::{Widget, WidgetObj};
+use widget_container::set_clipboard_text;
 use theme::Theme;
 use layout::Layout;
 use window::Window;
@@ -19,7 +21,8 @@ pub struct Label {
     caption: String,
     font: String,
     nanovg_font: Option<nanovg::Font>,
-    color: (u8, u8, u8, u8)
+    color: (u8, u8, u8, u8),
+    selectable: bool
 }
 
 impl Widget for Label {
@@ -124,6 +127,10 @@ impl Widget for Label {
         self.widget.focused = focused;
     }
 
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
     fn layout(&self) -> Option<&Box<Layout>> {
         self.widget.layout.as_ref()
     }
@@ -183,14 +190,42 @@ impl Widget for Label {
             None => nanovg_context.font_face(&self.font)
         }
 
+        let color = if self.widget.enabled_recursive() {
+            nanovg::Color::rgba(self.color.0, self.color.1, self.color.2, self.color.3)
+        } else {
+            match self.widget.theme {
+                Some(ref theme) => theme.borrow().disabled_text_color().to_nanovg_color(),
+                None => nanovg::Color::rgba(self.color.0, self.color.1, self.color.2, self.color.3 / 2)
+            }
+        };
+
         nanovg_context.font_size(self.widget.font_size() as f32);
-        nanovg_context.fill_color(nanovg::Color::rgba(self.color.0, self.color.1, self.color.2, self.color.3));
+
+        if self.selectable && self.widget.focused {
+            if let Some(ref theme) = self.widget.theme {
+                let mut bounds = [0f32; 4];
+                if self.widget.fixed_size.0 > 0 {
+                    nanovg_context.text_align(nanovg::LEFT | nanovg::TOP);
+                    nanovg_context.text_box_bounds(0.0, 0.0, self.widget.fixed_size.0 as f32, &self.caption, &mut bounds);
+                } else {
+                    nanovg_context.text_align(nanovg::LEFT | nanovg::TOP);
+                    nanovg_context.text_bounds(0.0, (self.widget.size.1 / 2) as f32, &self.caption, Some(&mut bounds));
+                }
+
+                nanovg_context.begin_path();
+                nanovg_context.rect(bounds[0], bounds[1], bounds[2] - bounds[0], bounds[3] - bounds[1]);
+                nanovg_context.fill_color(theme.borrow().text_selection_color().to_nanovg_color());
+                nanovg_context.fill();
+            }
+        }
+
+        nanovg_context.fill_color(color);
         if self.widget.fixed_size.0 > 0 {
             nanovg_context.text_align(nanovg::LEFT | nanovg::TOP);
-            nanovg_context.text_box(self.widget.pos.0 as f32, self.widget.pos.1 as f32, self.widget.fixed_size.0 as f32, &self.caption);
+            nanovg_context.text_box(0.0, 0.0, self.widget.fixed_size.0 as f32, &self.caption);
         } else {
             nanovg_context.text_align(nanovg::LEFT | nanovg::TOP);
-            nanovg_context.text(self.widget.pos.0 as f32, (self.widget.pos.1 + self.widget.size.1 / 2) as f32, &self.caption);
+            nanovg_context.text(0.0, (self.widget.size.1 / 2) as f32, &self.caption);
         }
     }
 
@@ -219,25 +254,55 @@ impl Widget for Label {
         return true;
     }
 
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
     fn contains(&self, p: (u32, u32)) -> bool {
         return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
     }
 
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
     fn request_focus(&self) {
         self.widget.request_focus();
     }
 
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
     // events
 
     fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
         self.widget.mouse_button_event(p, button, down, mods)
     }
 
-    fn mouse_motion_event(&self, p: (u32, u32), rel: (u32, u32), button: Mouse, mods: SDL_Keymod) -> bool {
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
         self.widget.mouse_motion_event(p, rel, button, mods)
     }
 
-    fn mouse_drag_event(&self, p: (u32, u32), rel: (u32, u32), button: Mouse, mods: SDL_Keymod) -> bool {
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
         self.widget.mouse_drag_event(p, rel, button, mods)
     }
 
@@ -245,7 +310,7 @@ impl Widget for Label {
         self.widget.mouse_enter_event(p, enter)
     }
 
-    fn scroll_event(&self, p: (u32, u32), rel: (u32, u32)) -> bool {
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
         self.widget.scroll_event(p, rel)
     }
 
@@ -254,11 +319,20 @@ impl Widget for Label {
     }
 
     fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        let copy = mods as u32 & (KMOD_CTRL | KMOD_GUI) != 0 && scancode == Some(Scancode::C);
+        if self.selectable && self.widget.focused && pressed && copy {
+            return set_clipboard_text(self, &self.caption)
+        }
+
         self.widget.keyboard_event(key, scancode, pressed, mods)
     }
 
-    fn keyboard_character_event(&self, codepoint: u32) -> bool {
-        self.widget.keyboard_character_event(codepoint)
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
     }
 
     // casts
@@ -279,7 +353,8 @@ impl Label {
             caption: caption,
             color: (255, 255, 255, 125),
             font: font_filename.clone(),
-            nanovg_font: Some(nanovg_context.create_font(&font_filename, &font_filename).unwrap())
+            nanovg_font: Some(nanovg_context.create_font(&font_filename, &font_filename).unwrap()),
+            selectable: false
         }))
     }
 
@@ -289,9 +364,21 @@ impl Label {
             caption: caption,
             color: (255, 255, 255, 125),
             font: font_filename.clone(),
-            nanovg_font: font
+            nanovg_font: font,
+            selectable: false
         }))
     }
 
     impl_get_set!(color, (u8, u8, u8, u8));
+
+    pub fn selectable(&self) -> bool {
+        self.selectable
+    }
+
+    /// A selectable label is selected as a whole when it has focus, and Ctrl+C
+    /// (Cmd+C on macOS) copies its caption to the clipboard.
+    pub fn set_selectable(&mut self, selectable: bool) {
+        self.selectable = selectable;
+        self.widget.cursor = if selectable { Cursor::IBeam } else { Cursor::Arrow };
+    }
 }
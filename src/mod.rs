@@ -8,10 +8,25 @@ mod gl {
 #[macro_use]
 pub mod common;
 pub mod resources;
+pub mod cursor;
+pub mod clipboard;
+pub mod shortcut;
 pub mod theme;
 pub mod layout;
 pub mod widget;
 pub mod widget_container;
 pub mod screen;
+pub mod screen_manager;
 pub mod label;
 pub mod window;
+pub mod text_buffer;
+pub mod text_box;
+pub mod number_box;
+pub mod text_area;
+pub mod check_box;
+pub mod slider;
+pub mod progress_bar;
+pub mod popup;
+pub mod popup_button;
+pub mod combo_box;
+pub mod scroll_panel;
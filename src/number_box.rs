@@ -0,0 +1,614 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::fmt::Display;
+use std::str::FromStr;
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use text_box::{TextAlignment, TextBox};
+use widget::Widget;
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+const SPIN_WIDTH: u32 = 14;
+// pixels the mouse has to move for one step when dragging the value
+const DRAG_STEP_DISTANCE: i32 = 10;
+
+/// The numeric types a NumberBox can edit.
+pub trait Numeric: Copy + PartialOrd + Display + FromStr + 'static {
+    fn from_f64(f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn is_integer() -> bool;
+    /// `self` moved by `steps` times `step`, stopping at the limits of the type.
+    fn add_steps(self, step: Self, steps: i32) -> Self;
+}
+
+/// Integer types, edited by an IntBox.
+pub trait Integer: Numeric {}
+
+/// Floating point types, edited by a FloatBox.
+pub trait Float: Numeric {}
+
+macro_rules! impl_integer {
+    ($($t:ident),*) => {
+        $(
+            impl Numeric for $t {
+                // casting a float that does not fit is undefined, so clamp it first
+                fn from_f64(val: f64) -> $t {
+                    if val.is_nan() {
+                        0
+                    } else if val <= $t::min_value() as f64 {
+                        $t::min_value()
+                    } else if val >= $t::max_value() as f64 {
+                        $t::max_value()
+                    } else {
+                        val as $t
+                    }
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn is_integer() -> bool {
+                    true
+                }
+
+                // integer math so large values keep every digit, saturating instead
+                // of wrapping
+                fn add_steps(self, step: $t, steps: i32) -> $t {
+                    let count = (steps as i64).abs() as u64;
+                    let offset = if count <= $t::max_value() as u64 { step.checked_mul(count as $t) } else { None };
+                    match offset {
+                        Some(val) if steps > 0 => self.saturating_add(val),
+                        Some(val) => self.saturating_sub(val),
+                        // the offset does not fit the type, but the result still can
+                        // when moving from one end of a signed range towards the other
+                        None => self.add_steps(step, steps / 2).add_steps(step, steps - steps / 2)
+                    }
+                }
+            }
+
+            impl Integer for $t {}
+        )*
+    }
+}
+
+macro_rules! impl_float {
+    ($($t:ident),*) => {
+        $(
+            impl Numeric for $t {
+                fn from_f64(val: f64) -> $t {
+                    val as $t
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn is_integer() -> bool {
+                    false
+                }
+
+                fn add_steps(self, step: $t, steps: i32) -> $t {
+                    self + steps as $t * step
+                }
+            }
+
+            impl Float for $t {}
+        )*
+    }
+}
+
+impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_float!(f32, f64);
+
+// A TextBox that only accepts numbers. Besides typing, the value can be changed
+// with the spin arrows, by dragging horizontally over the box and with the mouse
+// wheel while it is not being edited. `T` is any integer or float type; integer
+// boxes show the value as typed, float boxes with `decimals` digits.
+pub struct NumberBox<T: Numeric> {
+    text_box: TextBox,
+    value: Cell<T>,
+    min: Option<T>,
+    max: Option<T>,
+    step: T,
+    spinnable: bool,
+    decimals: Option<usize>,
+    // mouse x and value when a drag over the box started, and whether it moved
+    drag_start: Cell<Option<(u32, T)>>,
+    dragged: Cell<bool>,
+    on_change: Option<Box<Fn(T)>>
+}
+
+/// A NumberBox for an Integer type.
+pub type IntBox<T> = NumberBox<T>;
+
+/// A NumberBox for a Float type, usually shown with fixed decimals.
+pub type FloatBox<T> = NumberBox<T>;
+
+impl<T: Numeric> Widget for NumberBox<T> {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.text_box.parent()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.text_box.set_parent(parent)
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.text_box.children()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        self.text_box.children_mut()
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.text_box.id()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.text_box.set_id(id)
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.text_box.pos()
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.text_box.set_pos(p)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.text_box.size()
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.text_box.set_size(s)
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.text_box.fixed_size()
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.text_box.set_fixed_size(s)
+    }
+
+    fn font_size(&self) -> u32 {
+        self.text_box.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.text_box.set_font_size(s)
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.text_box.theme()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.text_box.set_theme(theme)
+    }
+
+    fn enabled(&self) -> bool {
+        self.text_box.enabled()
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.text_box.set_enabled(enabled)
+    }
+
+    fn tooltip(&self) -> String {
+        self.text_box.tooltip()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.text_box.set_tooltip(tooltip)
+    }
+
+    fn visible(&self) -> bool {
+        self.text_box.visible()
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.text_box.set_visible(visible)
+    }
+
+    fn focused(&self) -> bool {
+        self.text_box.focused()
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.text_box.set_focused(focused)
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.text_box.mouse_focus()
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.text_box.layout()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.text_box.set_layout(layout)
+    }
+
+    fn cursor(&self) -> Cursor {
+        if self.spinnable && !self.text_box.focused() {
+            Cursor::HResize
+        } else {
+            self.text_box.cursor()
+        }
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.text_box.set_cursor(cursor)
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.text_box.perform_layout(nanovg_context)
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        self.text_box.preferred_size(nanovg_context)
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        self.text_box.draw(nanovg_context);
+
+        if self.spinnable {
+            self.draw_spin_arrows(nanovg_context);
+        }
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        self.text_box.absolute_position()
+    }
+
+    fn visible_recursive(&self) -> bool {
+        self.text_box.visible_recursive()
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        self.text_box.enabled_recursive()
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        self.text_box.contains(p)
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.text_box.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.text_box.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.text_box.request_focus()
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.text_box.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.text_box.caret_rect()
+    }
+
+    // events
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        if button != Mouse::Left || !self.spinnable || !self.text_box.enabled_recursive() {
+            return self.text_box.mouse_button_event(p, button, down, mods)
+        }
+
+        let local_x = p.0.saturating_sub(self.text_box.pos().0);
+        let local_y = p.1.saturating_sub(self.text_box.pos().1);
+        if down && local_x < SPIN_WIDTH + 5 {
+            let steps = if local_y < self.text_box.size().1 / 2 { 1 } else { -1 };
+            self.change_value(self.add_steps(self.value.get(), steps));
+            return true
+        }
+
+        if self.text_box.focused() {
+            return self.text_box.mouse_button_event(p, button, down, mods)
+        }
+
+        if down {
+            self.drag_start.set(Some((p.0, self.value.get())));
+            self.dragged.set(false);
+        } else {
+            let clicked = self.drag_start.get().is_some() && !self.dragged.get();
+            self.drag_start.set(None);
+
+            // a click without dragging starts editing
+            if clicked {
+                self.text_box.mouse_button_event(p, button, true, mods);
+                self.text_box.mouse_button_event(p, button, false, mods);
+            }
+        }
+
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.text_box.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        match self.drag_start.get() {
+            Some((start_x, start_value)) if !self.text_box.focused() => {
+                let steps = (p.0 as i32 - start_x as i32) / DRAG_STEP_DISTANCE;
+                if steps != 0 {
+                    self.dragged.set(true);
+                }
+
+                if self.dragged.get() {
+                    self.change_value(self.add_steps(start_value, steps));
+                }
+                true
+            },
+            _ => self.text_box.mouse_drag_event(p, rel, button, mods)
+        }
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.text_box.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        if !self.spinnable || self.text_box.focused() || !self.text_box.enabled_recursive() || rel.1 == 0 {
+            return self.text_box.scroll_event(p, rel)
+        }
+
+        self.change_value(self.add_steps(self.value.get(), rel.1));
+        true
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        let handled = self.text_box.focus_event(focused);
+        if !focused {
+            self.commit_text();
+        }
+        handled
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        if self.text_box.focused() && self.text_box.editable() && pressed {
+            match scancode {
+                Some(Scancode::Up) => {
+                    self.change_value(self.add_steps(self.value.get(), 1));
+                    return true
+                },
+                Some(Scancode::Down) => {
+                    self.change_value(self.add_steps(self.value.get(), -1));
+                    return true
+                },
+                _ => {}
+            }
+        }
+
+        let handled = self.text_box.keyboard_event(key, scancode, pressed, mods);
+        if pressed && (scancode == Some(Scancode::Return) || scancode == Some(Scancode::KpEnter)) {
+            self.commit_text();
+        }
+        handled
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.text_box.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.text_box.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        self.text_box.as_window()
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        self.text_box.as_screen()
+    }
+}
+
+impl<T: Numeric> NumberBox<T> {
+    pub fn new(id: String, value: T) -> Rc<RefCell<NumberBox<T>>> {
+        let mut text_box = TextBox::create(id, format!("{}", value));
+        text_box.set_alignment(TextAlignment::Right);
+        text_box.set_format(Some(Box::new(|x: &str| x.trim().parse::<T>().is_ok())));
+
+        Rc::new(RefCell::new(NumberBox {
+            text_box: text_box,
+            value: Cell::new(value),
+            min: None,
+            max: None,
+            step: T::from_f64(1.0),
+            spinnable: false,
+            decimals: if T::is_integer() { None } else { Some(2) },
+            drag_start: Cell::new(None),
+            dragged: Cell::new(false),
+            on_change: None
+        }))
+    }
+
+    pub fn value(&self) -> T {
+        self.value.get()
+    }
+
+    /// Sets the value, clamped to min and max. Does not call on_change.
+    pub fn set_value(&mut self, value: T) {
+        let value = self.clamp(value);
+        self.value.set(value);
+        self.text_box.replace_value(self.format_value(value));
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.min
+    }
+
+    pub fn set_min(&mut self, min: Option<T>) {
+        self.min = min;
+        let value = self.value.get();
+        self.set_value(value);
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.max
+    }
+
+    pub fn set_max(&mut self, max: Option<T>) {
+        self.max = max;
+        let value = self.value.get();
+        self.set_value(value);
+    }
+
+    impl_get_set!(step, T);
+
+    pub fn spinnable(&self) -> bool {
+        self.spinnable
+    }
+
+    /// Shows the spin arrows and allows changing the value by dragging and scrolling.
+    pub fn set_spinnable(&mut self, spinnable: bool) {
+        self.spinnable = spinnable;
+        self.text_box.set_left_padding(if spinnable { SPIN_WIDTH } else { 0 });
+    }
+
+    pub fn decimals(&self) -> Option<usize> {
+        self.decimals
+    }
+
+    /// Number of decimals floats are shown with; None shows them as they are.
+    /// Integers ignore this.
+    pub fn set_decimals(&mut self, decimals: Option<usize>) {
+        self.decimals = decimals;
+        let value = self.value.get();
+        self.set_value(value);
+    }
+
+    pub fn units(&self) -> String {
+        self.text_box.units()
+    }
+
+    pub fn set_units(&mut self, units: String) {
+        self.text_box.set_units(units);
+    }
+
+    pub fn editable(&self) -> bool {
+        self.text_box.editable()
+    }
+
+    pub fn set_editable(&mut self, editable: bool) {
+        self.text_box.set_editable(editable);
+    }
+
+    /// Called with the new value whenever the user changes it.
+    pub fn set_on_change(&mut self, callback: Option<Box<Fn(T)>>) {
+        self.on_change = callback;
+    }
+
+    pub fn text_box(&self) -> &TextBox {
+        &self.text_box
+    }
+
+    fn clamp(&self, value: T) -> T {
+        let value = match self.min {
+            Some(min) if value < min => min,
+            _ => value
+        };
+
+        match self.max {
+            Some(max) if value > max => max,
+            _ => value
+        }
+    }
+
+    fn format_value(&self, value: T) -> String {
+        match self.decimals {
+            Some(val) if !T::is_integer() => format!("{:.*}", val, value.to_f64()),
+            _ => format!("{}", value)
+        }
+    }
+
+    // Applies a value chosen by the user: clamps it, shows it and reports changes.
+    fn change_value(&self, value: T) {
+        let value = self.clamp(value);
+        self.text_box.replace_value(self.format_value(value));
+
+        if value != self.value.get() {
+            self.value.set(value);
+            if let Some(ref val) = self.on_change {
+                val(value);
+            }
+        }
+    }
+
+    fn add_steps(&self, value: T, steps: i32) -> T {
+        value.add_steps(self.step, steps)
+    }
+
+    // Reads back the text after the text box committed an edit.
+    fn commit_text(&self) {
+        let text = self.text_box.value();
+        match text.trim().parse::<T>() {
+            Ok(val) => self.change_value(val),
+            Err(_) => self.text_box.replace_value(self.format_value(self.value.get()))
+        }
+    }
+
+    fn draw_spin_arrows(&self, nanovg_context: &nanovg::Context) {
+        let theme_rc = match self.text_box.theme() {
+            Some(val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let h = self.text_box.size().1 as f32;
+        let center_x = 5.0 + SPIN_WIDTH as f32 / 2.0 - 2.0;
+        let value = self.value.get();
+        let enabled = self.text_box.enabled_recursive();
+        let can_increase = enabled && self.max.map_or(true, |max| value < max);
+        let can_decrease = enabled && self.min.map_or(true, |min| value > min);
+
+        let arrow_color = |active: bool| if active {
+            theme.icon_color().to_nanovg_color()
+        } else {
+            theme.disabled_text_color().to_nanovg_color()
+        };
+
+        nanovg_context.begin_path();
+        nanovg_context.move_to(center_x - 4.0, h / 2.0 - 2.0);
+        nanovg_context.line_to(center_x + 4.0, h / 2.0 - 2.0);
+        nanovg_context.line_to(center_x, h / 2.0 - 7.0);
+        nanovg_context.close_path();
+        nanovg_context.fill_color(arrow_color(can_increase));
+        nanovg_context.fill();
+
+        nanovg_context.begin_path();
+        nanovg_context.move_to(center_x - 4.0, h / 2.0 + 2.0);
+        nanovg_context.line_to(center_x + 4.0, h / 2.0 + 2.0);
+        nanovg_context.line_to(center_x, h / 2.0 + 7.0);
+        nanovg_context.close_path();
+        nanovg_context.fill_color(arrow_color(can_decrease));
+        nanovg_context.fill();
+    }
+}
@@ -0,0 +1,415 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use std::cmp::max;
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use widget::{Widget, WidgetObj};
+use widget_container::draw_origin;
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+// length of the arrow pointing at the anchor, and the gap it bridges
+const ARROW_SIZE: u32 = 15;
+
+/// The side of the anchor a popup is shown on.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum PopupSide {
+    Left,
+    Right
+}
+
+/// A container shown above all windows next to an anchor widget, with an arrow
+/// pointing at it. Show it with `widget_container::show_overlay`, which makes it a
+/// child of the screen, or use a `PopupButton`.
+///
+/// The popup places itself whenever it is resized, so whenever it is laid out:
+/// on the preferred side of the anchor, or on the other side when it would leave
+/// the screen there.
+pub struct Popup {
+    widget: WidgetObj,
+    anchor: Option<Weak<RefCell<Widget>>>,
+    anchor_height: u32,
+    side: PopupSide,
+    placed_side: PopupSide,
+    arrow_offset: u32
+}
+
+impl Widget for Popup {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+        self.update_position();
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        self.widget.preferred_size(nanovg_context)
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        if !self.widget.visible {
+            return
+        }
+
+        if let Some(ref val) = self.widget.theme {
+            let theme = val.borrow();
+            let (w, h) = (self.widget.size.0 as f32, self.widget.size.1 as f32);
+            let ds = theme.window_dropshadow_size() as f32;
+            let cr = theme.window_corner_radius() as f32;
+            let arrow = ARROW_SIZE as f32;
+
+            let shadow_paint = nanovg_context.box_gradient(0.0, 0.0, w, h, cr * 2.0, ds * 2.0,
+                theme.dropshadow().to_nanovg_color(), theme.window_popup_transparent().to_nanovg_color());
+            nanovg_context.begin_path();
+            nanovg_context.rect(-ds, -ds, w + 2.0 * ds, h + 2.0 * ds);
+            nanovg_context.fill_paint(shadow_paint);
+            nanovg_context.fill();
+
+            nanovg_context.begin_path();
+            nanovg_context.rounded_rect(0.0, 0.0, w, h, cr);
+
+            // the arrow starts at the edge facing the anchor
+            let base_y = self.arrow_offset as f32;
+            let (base_x, sign) = match self.placed_side {
+                PopupSide::Right => (0.0, -1.0),
+                PopupSide::Left => (w, 1.0)
+            };
+            nanovg_context.move_to(base_x + arrow * sign, base_y);
+            nanovg_context.line_to(base_x - sign, base_y - arrow);
+            nanovg_context.line_to(base_x - sign, base_y + arrow);
+
+            nanovg_context.fill_color(theme.window_popup().to_nanovg_color());
+            nanovg_context.fill();
+        }
+
+        nanovg_context.save();
+        nanovg_context.intersect_scissor(0.0, 0.0, self.widget.size.0 as f32, self.widget.size.1 as f32);
+        self.widget.draw(nanovg_context);
+        nanovg_context.restore();
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    // the shadow and the arrow lie outside the bounds, the children are clipped in draw
+    fn clip_to_bounds(&self) -> bool {
+        false
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    // events
+    // Mouse events inside the popup never reach the windows below it.
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_button_event(p, button, down, mods);
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_motion_event(p, rel, button, mods);
+        true
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_drag_event(p, rel, button, mods)
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        self.widget.scroll_event(p, rel);
+        true
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        self.widget.keyboard_event(key, scancode, pressed, mods)
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl Popup {
+    pub fn new(id: String) -> Rc<RefCell<Popup>> {
+        Rc::new(RefCell::new(Popup {
+            widget: WidgetObj::new(id),
+            anchor: None,
+            anchor_height: 30,
+            side: PopupSide::Right,
+            placed_side: PopupSide::Right,
+            arrow_offset: 30
+        }))
+    }
+
+    impl_get_set!(anchor_height, u32);
+    impl_get_set!(side, PopupSide);
+
+    pub fn anchor(&self) -> Option<Rc<RefCell<Widget>>> {
+        match self.anchor {
+            Some(ref val) => val.upgrade(),
+            None => None
+        }
+    }
+
+    /// The widget the popup is shown next to. Only a weak reference is kept.
+    pub fn set_anchor(&mut self, anchor: Option<Rc<RefCell<Widget>>>) {
+        self.anchor = anchor.map(|x| Rc::downgrade(&x));
+        self.update_position();
+    }
+
+    /// The side the popup was last placed on, which is `side` unless the popup
+    /// did not fit there.
+    pub fn placed_side(&self) -> PopupSide {
+        self.placed_side
+    }
+
+    /// Moves the popup next to its anchor. The arrow points at the vertical center
+    /// of the anchor and is `anchor_height` below the top of the popup, unless the
+    /// popup has to move up to stay on the screen.
+    pub fn update_position(&mut self) {
+        let anchor = match self.anchor() {
+            Some(val) => val,
+            None => return
+        };
+        let ((anchor_x, anchor_y), (anchor_w, anchor_h)) = {
+            let anchor_borrow = anchor.borrow();
+            let (x, y) = draw_origin(&*anchor_borrow);
+            ((max(x, 0) as u32, max(y, 0) as u32), anchor_borrow.size())
+        };
+
+        let screen_size = match self.widget.parent {
+            Some(ref val) => val.upgrade().map(|x| x.borrow().size()),
+            None => None
+        };
+
+        let (w, h) = self.widget.size;
+        let fits_right = screen_size.map_or(true, |x| anchor_x + anchor_w + ARROW_SIZE + w <= x.0);
+        let fits_left = anchor_x >= w + ARROW_SIZE;
+        self.placed_side = match self.side {
+            PopupSide::Right if !fits_right && fits_left => PopupSide::Left,
+            PopupSide::Left if !fits_left => PopupSide::Right,
+            side => side
+        };
+
+        let x = match self.placed_side {
+            PopupSide::Right => anchor_x + anchor_w + ARROW_SIZE,
+            PopupSide::Left => anchor_x - w - ARROW_SIZE
+        };
+
+        let anchor_center = anchor_y + anchor_h / 2;
+        let mut y = anchor_center.saturating_sub(self.anchor_height);
+        if let Some((_, screen_h)) = screen_size {
+            if y + h > screen_h {
+                y = screen_h.saturating_sub(h);
+            }
+        }
+
+        self.widget.pos = (x, y);
+        self.arrow_offset = anchor_center - y;
+    }
+}
@@ -0,0 +1,424 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use widget::{Widget, WidgetObj};
+use widget_container::show_overlay;
+use popup::{Popup, PopupSide};
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+const TEXT_PADDING: f32 = 8.0;
+const ARROW_WIDTH: f32 = 20.0;
+
+/// A button that shows its popup next to itself while it is pushed. Add the
+/// contents of the popup to `popup()`.
+pub struct PopupButton {
+    widget: WidgetObj,
+    caption: String,
+    popup: Rc<RefCell<Popup>>
+}
+
+impl Widget for PopupButton {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        let fixed = self.widget.fixed_size;
+        if fixed.0 > 0 && fixed.1 > 0 {
+            return fixed
+        }
+
+        let font_size = self.widget.font_size() as f32;
+        nanovg_context.font_size(font_size);
+        nanovg_context.font_face(&self.widget.font());
+        let text_width = nanovg_context.text_bounds(0.0, 0.0, &self.caption, None);
+
+        (if fixed.0 > 0 { fixed.0 } else { (text_width + 2.0 * TEXT_PADDING + ARROW_WIDTH) as u32 },
+         if fixed.1 > 0 { fixed.1 } else { (font_size + 10.0) as u32 })
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        self.widget.draw(nanovg_context);
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let (w, h) = (self.widget.size.0 as f32, self.widget.size.1 as f32);
+        let corner_radius = theme.button_corner_radius() as f32;
+        let enabled = self.widget.enabled_recursive();
+
+        let (gradient_top, gradient_bot) = if self.popup_visible() {
+            (theme.button_gradient_top_pushed(), theme.button_gradient_bot_pushed())
+        } else if self.widget.mouse_focus && enabled {
+            (theme.button_gradient_top_focused(), theme.button_gradient_bot_focused())
+        } else {
+            (theme.button_gradient_top_unfocused(), theme.button_gradient_bot_unfocused())
+        };
+
+        let background = nanovg_context.linear_gradient(0.0, 0.0, 0.0, h,
+            gradient_top.to_nanovg_color(), gradient_bot.to_nanovg_color());
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(1.0, 1.0, w - 2.0, h - 2.0, corner_radius - 1.0);
+        nanovg_context.fill_paint(background);
+        nanovg_context.fill();
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(0.5, 1.5, w - 1.0, h - 2.0, corner_radius);
+        nanovg_context.stroke_color(theme.border_light().to_nanovg_color());
+        nanovg_context.stroke();
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(0.5, 0.5, w - 1.0, h - 2.0, corner_radius);
+        nanovg_context.stroke_color(theme.border_dark().to_nanovg_color());
+        nanovg_context.stroke();
+
+        let text_color = if enabled { theme.text_color() } else { theme.disabled_text_color() };
+        nanovg_context.font_size(self.widget.font_size() as f32);
+        nanovg_context.font_face(&self.widget.font());
+        nanovg_context.text_align(nanovg::CENTER | nanovg::MIDDLE);
+        nanovg_context.fill_color(text_color.to_nanovg_color());
+        nanovg_context.text(w / 2.0, h / 2.0, &self.caption);
+
+        // the arrow points to the side the popup opens on
+        let arrow_color = if enabled { theme.icon_color() } else { theme.disabled_text_color() };
+        let (arrow_x, sign) = match self.popup.borrow().side() {
+            PopupSide::Right => (w - ARROW_WIDTH / 2.0, 1.0),
+            PopupSide::Left => (ARROW_WIDTH / 2.0, -1.0)
+        };
+        nanovg_context.begin_path();
+        nanovg_context.move_to(arrow_x - 2.0 * sign, h / 2.0 - 4.0);
+        nanovg_context.line_to(arrow_x - 2.0 * sign, h / 2.0 + 4.0);
+        nanovg_context.line_to(arrow_x + 3.0 * sign, h / 2.0);
+        nanovg_context.close_path();
+        nanovg_context.fill_color(arrow_color.to_nanovg_color());
+        nanovg_context.fill();
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    // events
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        if button != Mouse::Left || !self.widget.enabled_recursive() {
+            return self.widget.mouse_button_event(p, button, down, mods)
+        }
+
+        if down {
+            if !self.widget.focused {
+                self.request_focus();
+            }
+            let visible = self.popup_visible();
+            self.set_popup_visible(!visible);
+        }
+
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_drag_event(p, rel, button, mods)
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        self.widget.scroll_event(p, rel)
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        if !self.widget.focused || !self.widget.enabled_recursive() {
+            return self.widget.keyboard_event(key, scancode, pressed, mods)
+        }
+
+        match scancode {
+            Some(Scancode::Return) | Some(Scancode::KpEnter) | Some(Scancode::Space) => {
+                if pressed {
+                    let visible = self.popup_visible();
+                    self.set_popup_visible(!visible);
+                }
+                true
+            },
+            Some(Scancode::Escape) if self.popup_visible() => {
+                if pressed {
+                    self.set_popup_visible(false);
+                }
+                true
+            },
+            _ => self.widget.keyboard_event(key, scancode, pressed, mods)
+        }
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl PopupButton {
+    pub fn new(id: String, caption: String) -> Rc<RefCell<PopupButton>> {
+        let popup = Popup::new(format!("{}.popup", id));
+        popup.borrow_mut().set_visible(false);
+
+        let button = Rc::new(RefCell::new(PopupButton {
+            widget: WidgetObj::new(id),
+            caption: caption,
+            popup: popup.clone()
+        }));
+
+        let anchor: Rc<RefCell<Widget>> = button.clone();
+        popup.borrow_mut().set_anchor(Some(anchor));
+        button
+    }
+
+    impl_get_set_clone!(caption, String);
+
+    pub fn popup(&self) -> Rc<RefCell<Popup>> {
+        self.popup.clone()
+    }
+
+    pub fn popup_visible(&self) -> bool {
+        self.popup.borrow().visible()
+    }
+
+    /// Shows the popup next to the button, or hides it. The popup gets the theme
+    /// of the button unless it has one of its own.
+    pub fn set_popup_visible(&self, visible: bool) {
+        {
+            let mut popup = self.popup.borrow_mut();
+            if popup.visible() == visible {
+                return
+            }
+
+            popup.set_visible(visible);
+            if !visible {
+                return
+            }
+
+            if popup.theme().is_none() {
+                popup.set_theme(self.widget.theme.clone());
+            }
+            popup.update_position();
+        }
+
+        show_overlay(self, self.popup.clone());
+    }
+}
@@ -0,0 +1,366 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use widget::{Widget, WidgetObj};
+use widget_container::{frame_time, request_redraw};
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+// milliseconds the indeterminate segment takes to cross the bar
+const SWEEP_DURATION: u32 = 1500;
+
+// width of the indeterminate segment relative to the bar
+const SWEEP_WIDTH: f32 = 0.25;
+
+pub struct ProgressBar {
+    widget: WidgetObj,
+    value: f32,
+    indeterminate: bool,
+    show_text: bool
+}
+
+impl Widget for ProgressBar {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, _: &nanovg::Context) -> (u32, u32) {
+        let fixed = self.widget.fixed_size;
+        let height = if self.show_text { self.widget.font_size() + 2 } else { 12 };
+        (if fixed.0 > 0 { fixed.0 } else { 70 },
+         if fixed.1 > 0 { fixed.1 } else { height })
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        self.widget.draw(nanovg_context);
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let (w, h) = (self.widget.size.0 as f32, self.widget.size.1 as f32);
+
+        let background = nanovg_context.box_gradient(1.0, 1.0, w - 2.0, h, 3.0, 4.0,
+            nanovg::Color::rgba(0, 0, 0, 32), nanovg::Color::rgba(0, 0, 0, 92));
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(0.0, 0.0, w, h, 3.0);
+        nanovg_context.fill_paint(background);
+        nanovg_context.fill();
+
+        let (bar_start, bar_width) = self.bar(w - 2.0, frame_time(self).unwrap_or(0));
+        if bar_width > 0.0 {
+            let bar = nanovg_context.box_gradient(bar_start, 0.0, bar_width + 1.5, h - 1.0, 3.0, 4.0,
+                nanovg::Color::rgba(220, 220, 220, 100), nanovg::Color::rgba(128, 128, 128, 100));
+            nanovg_context.begin_path();
+            nanovg_context.rounded_rect(1.0 + bar_start, 1.0, bar_width, h - 2.0, 3.0);
+            nanovg_context.fill_paint(bar);
+            nanovg_context.fill();
+        }
+
+        if self.indeterminate {
+            // keeps the segment moving for as long as the bar is shown
+            request_redraw(self);
+        } else if self.show_text {
+            nanovg_context.font_size(self.widget.font_size() as f32);
+            nanovg_context.font_face(&self.widget.font());
+            nanovg_context.text_align(nanovg::CENTER | nanovg::MIDDLE);
+            let text_color = if self.widget.enabled_recursive() { theme.text_color() } else { theme.disabled_text_color() };
+            nanovg_context.fill_color(text_color.to_nanovg_color());
+            nanovg_context.text(w / 2.0, h / 2.0, &self.percentage_text());
+        }
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    // events
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_button_event(p, button, down, mods)
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_drag_event(p, rel, button, mods)
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        self.widget.scroll_event(p, rel)
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        self.widget.keyboard_event(key, scancode, pressed, mods)
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl ProgressBar {
+    pub fn new(id: String) -> Rc<RefCell<ProgressBar>> {
+        Rc::new(RefCell::new(ProgressBar {
+            widget: WidgetObj::new(id),
+            value: 0.0,
+            indeterminate: false,
+            show_text: false
+        }))
+    }
+
+    impl_get_set!(indeterminate, bool);
+    impl_get_set!(show_text, bool);
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// The progress from 0 to 1; other values are clamped.
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value.max(0.0).min(1.0);
+    }
+
+    /// The text drawn over a determinate bar when show_text is set, e.g. "42%".
+    pub fn percentage_text(&self) -> String {
+        format!("{}%", (self.value * 100.0).round() as u32)
+    }
+
+    /// The filled part of a bar `width` pixels wide at `time` milliseconds, as
+    /// (start, width). An indeterminate bar sweeps a segment from left to right,
+    /// entering and leaving at the edges.
+    pub fn bar(&self, width: f32, time: u32) -> (f32, f32) {
+        if width <= 0.0 {
+            return (0.0, 0.0)
+        }
+
+        if !self.indeterminate {
+            return (0.0, (width * self.value).round())
+        }
+
+        let phase = (time % SWEEP_DURATION) as f32 / SWEEP_DURATION as f32;
+        let segment = width * SWEEP_WIDTH;
+        let start = phase * (width + segment) - segment;
+        let end = start + segment;
+        let start = start.max(0.0);
+        let end = end.min(width);
+        (start.round(), (end - start).max(0.0).round())
+    }
+}
@@ -2,32 +2,110 @@ extern crate nanovg;
 extern crate sdl2;
 extern crate sdl2_sys;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::ffi::CString;
+use std::mem;
+use std::ptr;
+use std::cmp::max;
 use std::rc::{Rc, Weak};
-use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::event::{Event, WindowEvent};
+use self::sdl2::keyboard::{Keycode, Mod, Scancode};
 use self::sdl2::mouse::Mouse;
 use self::sdl2_sys::keycode::SDL_Keymod;
+use gl;
 use common::Cursor;
+use cursor::{CursorBackend, CursorManager, RecordingCursorBackend, SdlCursorBackend};
+use clipboard::{Clipboard, MemoryClipboard, SdlClipboard};
+use shortcut::{KeyChord, ShortcutRegistry};
 use widget::{Widget, WidgetObj};
+use widget_container::{draw_children, draw_origin, find_rc, find_child_path, move_child_to_front, push_child, remove_child_by_id, to_parent_coords, update_hover_path};
 use theme::Theme;
 use layout::Layout;
 use window::Window;
 
+const TOOLTIP_WIDTH: f32 = 150.0;
+pub const TOOLTIP_FADE_DURATION: u32 = 250;
+
+/// How far a tooltip has come, as returned by tooltip_timing.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TooltipTiming {
+    // not shown yet, it appears in this many milliseconds
+    Waiting(u32),
+    // shown with this opacity, between 0 and 1
+    FadingIn(f32),
+    Shown
+}
+
+/// Tooltips appear once the mouse and keyboard have been idle for `delay`
+/// milliseconds since `last_interaction`, then fade in over TOOLTIP_FADE_DURATION.
+/// Both `last_interaction` and `now` are milliseconds since SDL started.
+pub fn tooltip_timing(last_interaction: u32, now: u32, delay: u32) -> TooltipTiming {
+    let elapsed = now.saturating_sub(last_interaction);
+    if elapsed < delay {
+        TooltipTiming::Waiting(delay - elapsed)
+    } else if elapsed < delay + TOOLTIP_FADE_DURATION {
+        TooltipTiming::FadingIn((elapsed - delay) as f32 / TOOLTIP_FADE_DURATION as f32)
+    } else {
+        TooltipTiming::Shown
+    }
+}
+
+/// The framebuffer size, pixel ratio and logical size of a screen whose window is
+/// `window_size` and whose GL drawable is `drawable_size`, both as SDL reports
+/// them. The drawable is larger than the window on high-DPI displays; SDL reports
+/// (0, 0) when it does not know it, which is treated as a pixel ratio of 1.
+/// Widgets are laid out in the logical size, the window size divided by `ui_scale`.
+pub fn framebuffer_metrics(window_size: (u32, u32), drawable_size: (i32, i32), ui_scale: f32) -> ((u32, u32), f32, (u32, u32)) {
+    let framebuffer_size = if drawable_size.0 > 0 && drawable_size.1 > 0 {
+        (drawable_size.0 as u32, drawable_size.1 as u32)
+    } else {
+        window_size
+    };
+    let pixel_ratio = if window_size.0 > 0 {
+        framebuffer_size.0 as f32 / window_size.0 as f32
+    } else {
+        1.0
+    };
+    let size = ((window_size.0 as f32 / ui_scale) as u32, (window_size.1 as f32 / ui_scale) as u32);
+    (framebuffer_size, pixel_ratio, size)
+}
+
 pub struct Screen {
     widget: WidgetObj,
-    nanovg_context: nanovg::Context,
+    nanovg_context: Option<nanovg::Context>,
+    window: *mut sdl2_sys::video::SDL_Window,
+    window_id: u32,
+    gl_context: sdl2_sys::video::SDL_GLContext,
     focussed_widgets: Vec<Rc<RefCell<Widget>>>,
+    pending_focus: RefCell<Option<Rc<RefCell<Widget>>>>,
+    window_size: (u32, u32),
     framebuffer_size: (u32, u32),
     pixel_ratio: f32,
-    mouse_state: i32,
-    modifiers: i32,
+    ui_scale: f32,
+    mouse_button: Option<Mouse>,
+    modifiers: SDL_Keymod,
     mouse_pos: (u32, u32),
     drag_active: bool,
-    drag_widget: Option<WidgetObj>,
+    drag_widget: Option<Rc<RefCell<Widget>>>,
+    text_input_active: Cell<bool>,
+    text_input_rect: Cell<Option<(i32, i32, i32, i32)>>,
+    hover_path: Vec<Rc<RefCell<Widget>>>,
+    overlays: Vec<(Rc<RefCell<Widget>>, String)>,
+    pending_overlays: RefCell<Vec<(Rc<RefCell<Widget>>, String)>>,
+    cursor_manager: CursorManager,
+    clipboard: RefCell<Box<Clipboard>>,
+    shortcuts: ShortcutRegistry,
+    clock: Box<Fn() -> u32>,
     last_interaction: u32,
+    redraw: Cell<bool>,
+    frame_time: Cell<u32>,
+    tooltip_delay: u32,
     process_events: bool,
     background: (f32, f32, f32),
-    caption: String
+    caption: String,
+    resize_callback: Option<Box<Fn((u32, u32))>>,
+    pre_event_callback: Option<Box<FnMut(&Event) -> bool>>,
+    unhandled_event_callback: Option<Box<FnMut(&Event)>>
 }
 
 impl Widget for Screen {
@@ -132,6 +210,10 @@ impl Widget for Screen {
         self.widget.focused = focused;
     }
 
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
     fn layout(&self) -> Option<&Box<Layout>> {
         self.widget.layout.as_ref()
     }
@@ -150,8 +232,10 @@ impl Widget for Screen {
 
     // misc
 
+    // the focused text widget may have moved
     fn perform_layout(&self, nanovg_context: &nanovg::Context) {
         self.widget.perform_layout(nanovg_context);
+        self.update_text_input();
     }
 
     fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
@@ -159,10 +243,7 @@ impl Widget for Screen {
     }
 
     fn draw(&self, nanovg_context: &nanovg::Context) {
-        for child in &self.widget.children {
-            let p_mut = child.borrow_mut();
-            p_mut.draw(nanovg_context);
-        }
+        draw_children(self, nanovg_context);
     }
 
     fn absolute_position(&self) -> (u32, u32) {
@@ -190,13 +271,43 @@ impl Widget for Screen {
         return true;
     }
 
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
     fn contains(&self, p: (u32, u32)) -> bool {
         return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
     }
 
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
     fn request_focus(&self) {
     }
 
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
     // events
     // TODO
 
@@ -204,11 +315,11 @@ impl Widget for Screen {
         self.widget.mouse_button_event(p, button, down, mods)
     }
 
-    fn mouse_motion_event(&self, p: (u32, u32), rel: (u32, u32), button: Mouse, mods: SDL_Keymod) -> bool {
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
         self.widget.mouse_motion_event(p, rel, button, mods)
     }
 
-    fn mouse_drag_event(&self, p: (u32, u32), rel: (u32, u32), button: Mouse, mods: SDL_Keymod) -> bool {
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
         self.widget.mouse_drag_event(p, rel, button, mods)
     }
 
@@ -216,7 +327,7 @@ impl Widget for Screen {
         self.widget.mouse_enter_event(p, enter)
     }
 
-    fn scroll_event(&self, p: (u32, u32), rel: (u32, u32)) -> bool {
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
         self.widget.scroll_event(p, rel)
     }
 
@@ -228,8 +339,12 @@ impl Widget for Screen {
         self.widget.keyboard_event(key, scancode, pressed, mods)
     }
 
-    fn keyboard_character_event(&self, codepoint: u32) -> bool {
-        self.widget.keyboard_character_event(codepoint)
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
     }
 
     // casts
@@ -244,32 +359,174 @@ impl Widget for Screen {
 }
 
 impl Screen {
+    /// Creates a screen drawing into `window`. The screen's nanovg context is bound
+    /// to the GL context that is current at this point, so make the window's GL
+    /// context current before calling this.
     pub fn new(id: String, caption: String, window: &mut sdl2::video::WindowRef) -> Rc<RefCell<Screen>> {
-
         let winsize = window.size();
         window.set_title(&caption);
 
         unsafe {
-            let mut screen: Screen = Screen {
-                widget: WidgetObj::new(id),
-                nanovg_context: nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES),
-                focussed_widgets: Vec::new(),
-                caption: caption,
-                framebuffer_size: winsize,
-                mouse_pos: (0, 0),
-                mouse_state: 0,
-                modifiers: 0,
-                drag_active: false,
-                drag_widget: None,
-                last_interaction: sdl2_sys::sdl::SDL_GetTicks(),
-                process_events: true,
-                background: (0.3, 0.3, 0.3),
-                pixel_ratio: 0.0
-            };
+            gl::load_with(|name| {
+                let name = CString::new(name).unwrap();
+                sdl2_sys::video::SDL_GL_GetProcAddress(name.as_ptr()) as *const _
+            });
+        }
 
-            screen.set_size(winsize);
-            Rc::new(RefCell::new(screen))
+        let mut screen = Screen::create(id, caption, winsize);
+        unsafe {
+            screen.nanovg_context = Some(nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES));
+            screen.window = window.raw();
+            screen.window_id = sdl2_sys::video::SDL_GetWindowID(screen.window);
+            screen.gl_context = sdl2_sys::video::SDL_GL_GetCurrentContext();
         }
+        screen.cursor_manager = CursorManager::new(Box::new(SdlCursorBackend::new()));
+        screen.clipboard = RefCell::new(Box::new(SdlClipboard::new()));
+        screen.clock = Box::new(|| unsafe { sdl2_sys::sdl::SDL_GetTicks() });
+        screen.last_interaction = screen.ticks();
+        screen.frame_time.set(screen.last_interaction);
+        screen.update_framebuffer(winsize);
+
+        // SDL starts with text input enabled; it is only turned on while a
+        // widget that accepts text has focus
+        unsafe { sdl2_sys::keyboard::SDL_StopTextInput() };
+
+        Rc::new(RefCell::new(screen))
+    }
+
+    /// Creates a screen of `size` that is not attached to an SDL window, e.g. to
+    /// dispatch events to widgets in tests. It makes no SDL calls and has no nanovg
+    /// context, so it lays out and draws nothing; pass a context to draw or
+    /// perform_layout directly instead. Cursor changes go to a
+    /// RecordingCursorBackend, the clipboard is a MemoryClipboard and the clock
+    /// stands still at 0; replace them with set_cursor_backend, set_clipboard and
+    /// set_clock.
+    pub fn new_debug(id: String, size: (u32, u32)) -> Rc<RefCell<Screen>> {
+        Rc::new(RefCell::new(Screen::create(id, String::new(), size)))
+    }
+
+    fn create(id: String, caption: String, winsize: (u32, u32)) -> Screen {
+        let mut screen: Screen = Screen {
+            widget: WidgetObj::new(id),
+            nanovg_context: None,
+            window: ptr::null_mut(),
+            window_id: 0,
+            gl_context: ptr::null_mut(),
+            focussed_widgets: Vec::new(),
+            pending_focus: RefCell::new(None),
+            caption: caption,
+            window_size: winsize,
+            framebuffer_size: winsize,
+            mouse_pos: (0, 0),
+            mouse_button: None,
+            modifiers: 0,
+            drag_active: false,
+            drag_widget: None,
+            text_input_active: Cell::new(false),
+            text_input_rect: Cell::new(None),
+            hover_path: Vec::new(),
+            overlays: Vec::new(),
+            pending_overlays: RefCell::new(Vec::new()),
+            cursor_manager: CursorManager::new(Box::new(RecordingCursorBackend::new())),
+            clipboard: RefCell::new(Box::new(MemoryClipboard::new())),
+            shortcuts: ShortcutRegistry::new(),
+            clock: Box::new(|| 0),
+            last_interaction: 0,
+            redraw: Cell::new(true),
+            frame_time: Cell::new(0),
+            tooltip_delay: 500,
+            process_events: true,
+            background: (0.3, 0.3, 0.3),
+            pixel_ratio: 1.0,
+            ui_scale: 1.0,
+            resize_callback: None,
+            pre_event_callback: None,
+            unhandled_event_callback: None
+        };
+
+        screen.update_framebuffer(winsize);
+        screen
+    }
+
+    /// Clears the window with the background color, sets up the GL state nanovg
+    /// needs and draws all widgets. Nothing is drawn unless a redraw is needed;
+    /// returns whether a frame was drawn and the window should be swapped. A screen
+    /// without a nanovg context (see new_debug) only updates its frame time and
+    /// input method state.
+    pub fn draw_all(&self) -> bool {
+        if !self.needs_redraw() {
+            return false
+        }
+
+        self.redraw.set(false);
+
+        if self.nanovg_context.is_none() {
+            self.frame_time.set(self.ticks());
+            self.update_text_input();
+            return true
+        }
+
+        unsafe {
+            gl::Viewport(0, 0, self.framebuffer_size.0 as i32, self.framebuffer_size.1 as i32);
+            gl::ClearColor(self.background.0, self.background.1, self.background.2, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::Enable(gl::CULL_FACE);
+            gl::Disable(gl::DEPTH_TEST);
+        }
+
+        self.draw_widgets();
+
+        // the caret of the focused widget is only known once it was drawn
+        self.update_text_input();
+        true
+    }
+
+    /// Marks the screen as dirty. Events handled by the screen do this already;
+    /// call it after changing widgets from outside of an event.
+    pub fn request_redraw(&self) {
+        self.redraw.set(true);
+    }
+
+    /// Whether the next draw_all call will draw, either because the screen is dirty
+    /// or because a tooltip is about to appear or still fading in. When this is
+    /// false the application can block on SDL_WaitEvent, using redraw_timeout as
+    /// the timeout.
+    pub fn needs_redraw(&self) -> bool {
+        self.redraw.get() || self.redraw_timeout() == Some(0)
+    }
+
+    /// Milliseconds until a pending tooltip needs to be drawn, or None if nothing
+    /// is waiting to be animated.
+    pub fn redraw_timeout(&self) -> Option<u32> {
+        let has_tooltip = match self.hover_path.last() {
+            Some(val) => val.borrow().tooltip().len() > 0,
+            None => false
+        };
+
+        if !has_tooltip {
+            return None
+        }
+
+        let now = self.ticks();
+        match tooltip_timing(self.last_interaction, now, self.tooltip_delay) {
+            TooltipTiming::Waiting(val) => Some(val),
+            TooltipTiming::FadingIn(_) => Some(0),
+            // one more frame draws it fully opaque, unless the last one did already
+            TooltipTiming::Shown => {
+                match tooltip_timing(self.last_interaction, self.frame_time.get(), self.tooltip_delay) {
+                    TooltipTiming::Shown => None,
+                    _ => Some(0)
+                }
+            }
+        }
+    }
+
+    /// Milliseconds since SDL was initialized, taken once when the current (or
+    /// last) frame started. Animations use it so all widgets agree on the time.
+    pub fn frame_time(&self) -> u32 {
+        self.frame_time.get()
     }
 
     pub fn draw_widgets(&self) {
@@ -282,26 +539,758 @@ impl Screen {
             self.widget.fixed_size.0, self.widget.fixed_size.1,
             self.widget.pos.0, self.widget.pos.1);*/
 
-        self.nanovg_context.begin_frame(self.widget.size.0, self.widget.size.1, 1.0);
+        let nanovg_context = match self.nanovg_context {
+            Some(ref val) => val,
+            None => return
+        };
+
+        self.frame_time.set(self.ticks());
+        nanovg_context.begin_frame(self.widget.size.0, self.widget.size.1, self.pixel_ratio * self.ui_scale);
+
+        self.draw(nanovg_context);
+        self.draw_tooltip(nanovg_context);
 
-        self.draw(&self.nanovg_context);
+        nanovg_context.end_frame();
+    }
 
-        self.nanovg_context.end_frame();
+    // Shows the tooltip of the widget under the mouse once the mouse has been idle
+    // for tooltip_delay milliseconds. Any mouse or keyboard interaction resets the timer.
+    fn draw_tooltip(&self, nanovg_context: &nanovg::Context) {
+        let fade = match tooltip_timing(self.last_interaction, self.frame_time.get(), self.tooltip_delay) {
+            TooltipTiming::Waiting(_) => return,
+            TooltipTiming::FadingIn(val) => val,
+            TooltipTiming::Shown => 1.0
+        };
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+
+        let widget = match self.hover_path.last() {
+            Some(val) => val.clone(),
+            None => return
+        };
+
+        let widget_borrow = widget.borrow();
+        let tooltip = widget_borrow.tooltip();
+        if tooltip.len() == 0 {
+            return
+        }
+
+        let theme = theme_rc.borrow();
+        let (origin_x, origin_y) = draw_origin(&*widget_borrow);
+        let anchor = (origin_x as f32 + widget_borrow.size().0 as f32 / 2.0, (origin_y + widget_borrow.size().1 as i32) as f32 + 10.0);
+        let mut bounds = [0f32; 4];
+
+        nanovg_context.font_face(&theme.standard_font());
+        nanovg_context.font_size(theme.standard_font_size() as f32);
+        nanovg_context.text_align(nanovg::LEFT | nanovg::TOP);
+
+        // long tooltips are wrapped at TOOLTIP_WIDTH, short ones stay on a single line
+        let line_width = nanovg_context.text_bounds(0.0, 0.0, &tooltip, None);
+        let wrap = line_width > TOOLTIP_WIDTH;
+        let width = if wrap { TOOLTIP_WIDTH } else { line_width };
+        let max_x = (self.widget.size.0 as f32 - width - 4.0).max(4.0);
+        let x = (anchor.0 - width / 2.0).max(4.0).min(max_x);
+
+        if wrap {
+            nanovg_context.text_box_bounds(x, anchor.1, width, &tooltip, &mut bounds);
+        } else {
+            nanovg_context.text_bounds(x, anchor.1, &tooltip, Some(&mut bounds));
+        }
+
+        nanovg_context.save();
+        nanovg_context.global_alpha(fade * 0.8);
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(bounds[0] - 4.0, bounds[1] - 4.0, bounds[2] - bounds[0] + 8.0, bounds[3] - bounds[1] + 8.0, 3.0);
+        nanovg_context.move_to(anchor.0, bounds[1] - 10.0);
+        nanovg_context.line_to(anchor.0 + 7.0, bounds[1] - 3.0);
+        nanovg_context.line_to(anchor.0 - 7.0, bounds[1] - 3.0);
+        nanovg_context.fill_color(theme.tooltip_background().to_nanovg_color());
+        nanovg_context.fill();
+
+        nanovg_context.fill_color(theme.tooltip_text_color().to_nanovg_color());
+        if wrap {
+            nanovg_context.text_box(x, anchor.1, width, &tooltip);
+        } else {
+            nanovg_context.text(x, anchor.1, &tooltip);
+        }
+
+        nanovg_context.restore();
     }
 
     pub fn set_background(&mut self, background_color: (f32, f32, f32)) {
         self.background = background_color;
     }
 
-    pub fn nanovg_context(&self) -> &nanovg::Context {
-        &self.nanovg_context
+    impl_get_set!(tooltip_delay, u32);
+
+    pub fn framebuffer_size(&self) -> (u32, u32) {
+        self.framebuffer_size
+    }
+
+    pub fn pixel_ratio(&self) -> f32 {
+        self.pixel_ratio
+    }
+
+    pub fn ui_scale(&self) -> f32 {
+        self.ui_scale
+    }
+
+    /// Scales the whole UI by `ui_scale` on top of the display's pixel ratio, e.g.
+    /// 1.5 draws every widget 50% larger. The screen's size shrinks accordingly and
+    /// its children are laid out again. Scales that are not a positive number are
+    /// rejected and leave the screen unchanged.
+    pub fn set_ui_scale(screen: Rc<RefCell<Screen>>, ui_scale: f32) -> Result<(), String> {
+        if !ui_scale.is_finite() || ui_scale <= 0.0 {
+            return Err(format!("Invalid UI scale {}", ui_scale))
+        }
+
+        {
+            let mut screen_mut = screen.borrow_mut();
+            let window_size = screen_mut.window_size;
+            screen_mut.ui_scale = ui_scale;
+            screen_mut.update_framebuffer(window_size);
+        }
+
+        let screen_borrow = screen.borrow();
+        if let Some(ref val) = screen_borrow.nanovg_context {
+            screen_borrow.perform_layout(val);
+        }
+        Ok(())
+    }
+
+    // The drawable size differs from the window size on high-DPI displays. Widgets
+    // work in logical units: the window size divided by the UI scale.
+    fn update_framebuffer(&mut self, window_size: (u32, u32)) {
+        let mut drawable_size = (0i32, 0i32);
+        if !self.window.is_null() {
+            unsafe {
+                sdl2_sys::video::SDL_GL_GetDrawableSize(self.window, &mut drawable_size.0, &mut drawable_size.1);
+            }
+        }
+
+        let (framebuffer_size, pixel_ratio, size) = framebuffer_metrics(window_size, drawable_size, self.ui_scale);
+        self.window_size = window_size;
+        self.framebuffer_size = framebuffer_size;
+        self.pixel_ratio = pixel_ratio;
+        self.widget.size = size;
+    }
+
+    // SDL reports mouse positions in window coordinates.
+    fn to_ui_coords(&self, p: (i32, i32)) -> (u32, u32) {
+        ((max(p.0, 0) as f32 / self.ui_scale) as u32, (max(p.1, 0) as f32 / self.ui_scale) as u32)
+    }
+
+    pub fn set_resize_callback(&mut self, callback: Option<Box<Fn((u32, u32))>>) {
+        self.resize_callback = callback;
+    }
+
+    pub fn set_pre_event_callback(&mut self, callback: Option<Box<FnMut(&Event) -> bool>>) {
+        self.pre_event_callback = callback;
+    }
+
+    pub fn set_unhandled_event_callback(&mut self, callback: Option<Box<FnMut(&Event)>>) {
+        self.unhandled_event_callback = callback;
     }
 
+    impl_get_set!(process_events, bool);
+
+    pub fn shortcuts(&self) -> &ShortcutRegistry {
+        &self.shortcuts
+    }
+
+    pub fn shortcuts_mut(&mut self) -> &mut ShortcutRegistry {
+        &mut self.shortcuts
+    }
+
+    pub fn set_cursor_backend(&mut self, backend: Box<CursorBackend>) {
+        self.cursor_manager = CursorManager::new(backend);
+    }
+
+    pub fn set_clipboard(&mut self, clipboard: Box<Clipboard>) {
+        self.clipboard = RefCell::new(clipboard);
+    }
+
+    // The clipboard is used by widgets during event dispatch, while the screen is
+    // only borrowed immutably.
+    pub fn clipboard_text(&self) -> Option<String> {
+        self.clipboard.borrow().text()
+    }
+
+    pub fn set_clipboard_text(&self, text: &str) {
+        self.clipboard.borrow_mut().set_text(text);
+    }
+
+    /// The nanovg context the screen draws with, None for screens created by
+    /// new_debug.
+    pub fn nanovg_context(&self) -> Option<&nanovg::Context> {
+        self.nanovg_context.as_ref()
+    }
+
+    /// Replaces the source of the current time in milliseconds, which SDL_GetTicks
+    /// provides for screens created by new. Tooltips and redraw_timeout use it.
+    pub fn set_clock(&mut self, clock: Box<Fn() -> u32>) {
+        self.clock = clock;
+    }
+
+    fn ticks(&self) -> u32 {
+        (self.clock)()
+    }
+
+    pub fn window_id(&self) -> u32 {
+        self.window_id
+    }
+
+    /// Makes the GL context the screen was created with current on its window.
+    /// Needed before drawing or creating nanovg resources when there is more than
+    /// one screen. Returns false if SDL could not switch the context.
+    pub fn make_current(&self) -> bool {
+        !self.window.is_null() && unsafe { sdl2_sys::video::SDL_GL_MakeCurrent(self.window, self.gl_context) == 0 }
+    }
+
+    pub fn swap_window(&self) {
+        if !self.window.is_null() {
+            unsafe { sdl2_sys::video::SDL_GL_SwapWindow(self.window) };
+        }
+    }
+
+    // Widgets request focus while the tree is borrowed for event dispatch, so the
+    // request is stored and applied by handle_event once dispatch has finished.
     pub fn update_focus(&self, widget: &Widget) {
-        /*for child in &self.focussed_widgets {
-            if w.borrow().focussed() {
-                //w.focus_event(false);
+        *self.pending_focus.borrow_mut() = find_rc(widget);
+    }
+
+    pub fn focus_path(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.focussed_widgets.clone()
+    }
+
+    /// Focuses `widget` and all of its ancestors below the screen, sending
+    /// focus_event(false) to widgets that lose focus. Disabled widgets cannot be
+    /// focused; passing `None` clears the focus.
+    pub fn set_focus(screen: Rc<RefCell<Screen>>, widget: Option<Rc<RefCell<Widget>>>) {
+        let mut new_path: Vec<Rc<RefCell<Widget>>> = Vec::new();
+
+        if let Some(ref val) = widget {
+            if !val.borrow().enabled_recursive() {
+                return
             }
-        }*/
+        }
+
+        let mut current = widget;
+        while let Some(val) = current {
+            if val.borrow().as_screen().is_some() {
+                break;
+            }
+
+            new_path.insert(0, val.clone());
+            current = match val.borrow().parent() {
+                Some(parent) => parent.upgrade(),
+                None => None
+            };
+        }
+
+        // an overlay closes when the focus moves away from both it and its owner
+        screen.borrow().close_overlays_outside(&new_path);
+
+        let old_path = screen.borrow().focussed_widgets.clone();
+        for old in &old_path {
+            let old_id = old.borrow().id();
+            if !new_path.iter().any(|x| x.borrow().id() == old_id) && old.borrow().focused() {
+                old.borrow_mut().focus_event(false);
+            }
+        }
+
+        for new in &new_path {
+            if !new.borrow().focused() {
+                new.borrow_mut().focus_event(true);
+            }
+        }
+
+        let mut window_id: Option<String> = None;
+        if let Some(first) = new_path.first() {
+            if first.borrow().as_window().is_some() {
+                window_id = Some(first.borrow().id());
+            }
+        }
+
+        screen.borrow_mut().focussed_widgets = new_path;
+
+        if let Some(id) = window_id {
+            move_child_to_front(screen.clone(), id);
+            Screen::raise_overlays(screen.clone());
+        }
+
+        screen.borrow().update_text_input();
+    }
+
+    // Starts SDL text input while the focused widget accepts text and stops it
+    // otherwise. The IME candidate window is placed next to the caret of the
+    // focused widget, or over the whole widget before its caret was drawn. Runs
+    // after focus changes, layouts, dispatched events and draws, which can all
+    // move the caret.
+    fn update_text_input(&self) {
+        let target = match self.focussed_widgets.last() {
+            Some(val) if val.borrow().accepts_text_input() => Some(val.clone()),
+            _ => None
+        };
+
+        let rect = target.map(|val| {
+            let widget_borrow = val.borrow();
+            let (origin_x, origin_y) = draw_origin(&*widget_borrow);
+            let (x, y, width, height) = match widget_borrow.caret_rect() {
+                Some(caret) => caret,
+                None => (0.0, 0.0, widget_borrow.size().0 as f32, widget_borrow.size().1 as f32)
+            };
+            (((origin_x as f32 + x) * self.ui_scale) as i32, ((origin_y as f32 + y) * self.ui_scale) as i32,
+             (width * self.ui_scale).ceil() as i32, (height * self.ui_scale).ceil() as i32)
+        });
+
+        // a screen without a window only keeps track of the state
+        let has_window = !self.window.is_null();
+        match rect {
+            Some(val) => {
+                if !self.text_input_active.get() {
+                    if has_window {
+                        unsafe { sdl2_sys::keyboard::SDL_StartTextInput() };
+                    }
+                    self.text_input_active.set(true);
+                }
+                if has_window && self.text_input_rect.get() != Some(val) {
+                    let mut sdl_rect = sdl2_sys::rect::SDL_Rect { x: val.0, y: val.1, w: val.2, h: val.3 };
+                    unsafe { sdl2_sys::keyboard::SDL_SetTextInputRect(&mut sdl_rect) };
+                }
+            },
+            None => {
+                if self.text_input_active.get() {
+                    if has_window {
+                        unsafe { sdl2_sys::keyboard::SDL_StopTextInput() };
+                    }
+                    self.text_input_active.set(false);
+                }
+            }
+        }
+        self.text_input_rect.set(rect);
+    }
+
+    pub fn text_input_active(&self) -> bool {
+        self.text_input_active.get()
+    }
+
+    /// Where the candidate window of an input method goes, (x, y, width, height)
+    /// in window coordinates. None while text input is stopped.
+    pub fn text_input_rect(&self) -> Option<(i32, i32, i32, i32)> {
+        self.text_input_rect.get()
+    }
+
+    /// Shows `overlay` above all windows once the current event is dispatched,
+    /// e.g. the list of a combo box. The overlay becomes a child of the screen, so
+    /// its position is in screen coordinates, and is then sized and laid out. It is
+    /// hidden by a click outside of it and outside of the widget with id
+    /// `owner_id`, or when the focus moves away from both; clicks on the owner are
+    /// left to the owner, so it can close the overlay itself.
+    pub fn show_overlay(&self, overlay: Rc<RefCell<Widget>>, owner_id: String) {
+        self.pending_overlays.borrow_mut().push((overlay, owner_id));
+        self.request_redraw();
+    }
+
+    /// The overlays currently attached to the screen, bottom-most first.
+    pub fn overlays(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.overlays.iter().map(|x| x.0.clone()).collect()
+    }
+
+    // Attaches the overlays requested during the last event and detaches the
+    // ones that were hidden since.
+    fn apply_pending_overlays(screen: Rc<RefCell<Screen>>) {
+        let pending = mem::replace(&mut *screen.borrow().pending_overlays.borrow_mut(), Vec::new());
+        let old_overlays = mem::replace(&mut screen.borrow_mut().overlays, Vec::new());
+        let mut overlays = Vec::new();
+
+        for (overlay, owner_id) in old_overlays {
+            let id = overlay.borrow().id();
+            if overlay.borrow().visible() && !pending.iter().any(|x| x.0.borrow().id() == id) {
+                overlays.push((overlay, owner_id));
+            } else {
+                remove_child_by_id(screen.clone(), id);
+            }
+        }
+
+        for (overlay, owner_id) in pending {
+            let id = overlay.borrow().id();
+            if !overlay.borrow().visible() || overlays.iter().any(|x| x.0.borrow().id() == id) {
+                continue
+            }
+            push_child(screen.clone(), overlay.clone());
+            Screen::layout_overlay(screen.clone(), overlay.clone());
+            overlays.push((overlay, owner_id));
+        }
+
+        screen.borrow_mut().overlays = overlays;
+    }
+
+    // Sizes a newly shown overlay like perform_layout sizes the other children.
+    fn layout_overlay(screen: Rc<RefCell<Screen>>, overlay: Rc<RefCell<Widget>>) {
+        let screen_borrow = screen.borrow();
+        let nanovg_context = match screen_borrow.nanovg_context {
+            Some(ref val) => val,
+            None => return
+        };
+        let preferred = overlay.borrow().preferred_size(nanovg_context);
+        let fixed = overlay.borrow().fixed_size();
+        let size = (if fixed.0 > 0 { fixed.0 } else { preferred.0 },
+                    if fixed.1 > 0 { fixed.1 } else { preferred.1 });
+
+        overlay.borrow_mut().set_size(size);
+        overlay.borrow().perform_layout(nanovg_context);
+    }
+
+    // Keeps the overlays on top after a window was moved to the front.
+    fn raise_overlays(screen: Rc<RefCell<Screen>>) {
+        let ids: Vec<String> = screen.borrow().overlays.iter().map(|x| x.0.borrow().id()).collect();
+        for id in ids {
+            move_child_to_front(screen.clone(), id);
+        }
+    }
+
+    fn close_overlays_outside(&self, path: &[Rc<RefCell<Widget>>]) {
+        let path_ids: Vec<String> = path.iter().map(|x| x.borrow().id()).collect();
+        for &(ref overlay, ref owner_id) in &self.overlays {
+            let id = overlay.borrow().id();
+            if !path_ids.contains(&id) && !path_ids.contains(owner_id) {
+                overlay.borrow_mut().set_visible(false);
+            }
+        }
+    }
+
+    /// Dispatches an SDL event to the widgets of this screen. Returns true when a
+    /// widget (or the pre-event callback) handled it.
+    ///
+    /// The pre-event callback sees every event first and consumes it by returning
+    /// true. Window events are always processed, input events only while
+    /// process_events is set. Events nothing handled are passed on to the
+    /// unhandled-event callback.
+    pub fn handle_event(screen: Rc<RefCell<Screen>>, event: &Event) -> bool {
+        // callbacks are taken out while they run, so they are free to modify the
+        // screen; one they replaced is not put back
+        let mut pre_callback = screen.borrow_mut().pre_event_callback.take();
+        let consumed = match pre_callback {
+            Some(ref mut val) => val(event),
+            None => false
+        };
+        if screen.borrow().pre_event_callback.is_none() {
+            screen.borrow_mut().pre_event_callback = pre_callback;
+        }
+
+        if consumed {
+            return true
+        }
+
+        let process_events = screen.borrow().process_events;
+        let dispatched = match *event {
+            Event::Window { win_event: WindowEvent::Resized(width, height), .. } |
+            Event::Window { win_event: WindowEvent::SizeChanged(width, height), .. } => {
+                Some(Screen::resize_callback_event(screen.clone(), (width, height)))
+            },
+            Event::Window { win_event: WindowEvent::Leave, .. } => {
+                Screen::update_hover(screen.clone(), None);
+                Some(false)
+            },
+            Event::Window { win_event: WindowEvent::Exposed, .. } => Some(false),
+            _ if !process_events => None,
+            Event::MouseMotion { x, y, xrel, yrel, .. } => {
+                Some(Screen::mouse_motion_callback(screen.clone(), (x, y), (xrel, yrel)))
+            },
+            Event::MouseButtonDown { mouse_btn, x, y, .. } => {
+                Some(Screen::mouse_button_callback(screen.clone(), mouse_btn, true, (x, y)))
+            },
+            Event::MouseButtonUp { mouse_btn, x, y, .. } => {
+                Some(Screen::mouse_button_callback(screen.clone(), mouse_btn, false, (x, y)))
+            },
+            Event::MouseWheel { x, y, .. } => {
+                Some(Screen::scroll_callback(screen.clone(), (x, y)))
+            },
+            Event::KeyDown { keymod, keycode, scancode, repeat, .. } => {
+                Some(Screen::keyboard_callback(screen.clone(), keymod, keycode, scancode, true, repeat))
+            },
+            Event::KeyUp { keymod, keycode, scancode, repeat, .. } => {
+                Some(Screen::keyboard_callback(screen.clone(), keymod, keycode, scancode, false, repeat))
+            },
+            Event::TextInput { ref text, .. } => {
+                Some(Screen::text_input_callback(screen.clone(), text))
+            },
+            Event::TextEditing { ref text, start, length, .. } => {
+                Some(Screen::text_editing_callback(screen.clone(), text, start, length))
+            },
+            _ => None
+        };
+
+        if dispatched.is_some() {
+            screen.borrow().request_redraw();
+            Screen::apply_pending_overlays(screen.clone());
+
+            let pending = screen.borrow().pending_focus.borrow_mut().take();
+            if pending.is_some() {
+                Screen::set_focus(screen.clone(), pending);
+            }
+
+            // typing moves the caret and scrolling the focused widget
+            screen.borrow().update_text_input();
+        }
+
+        let handled = dispatched.unwrap_or(false);
+        if !handled {
+            let mut unhandled_callback = screen.borrow_mut().unhandled_event_callback.take();
+            if let Some(ref mut val) = unhandled_callback {
+                val(event);
+            }
+            if screen.borrow().unhandled_event_callback.is_none() {
+                screen.borrow_mut().unhandled_event_callback = unhandled_callback;
+            }
+        }
+
+        handled
+    }
+
+    // SDL sends both Resized and SizeChanged for user resizes, the second one is a no-op.
+    fn resize_callback_event(screen: Rc<RefCell<Screen>>, window_size: (i32, i32)) -> bool {
+        let window_size = (max(window_size.0, 0) as u32, max(window_size.1, 0) as u32);
+        let size: (u32, u32);
+        {
+            let mut screen_mut = screen.borrow_mut();
+            if screen_mut.window_size == window_size {
+                return false
+            }
+
+            screen_mut.update_framebuffer(window_size);
+            screen_mut.last_interaction = screen_mut.ticks();
+            size = screen_mut.widget.size;
+        }
+
+        {
+            let screen_borrow = screen.borrow();
+            if let Some(ref val) = screen_borrow.nanovg_context {
+                screen_borrow.perform_layout(val);
+            }
+        }
+
+        // the callback is taken out while it runs, so it is free to modify the screen
+        let callback = screen.borrow_mut().resize_callback.take();
+        if let Some(ref val) = callback {
+            val(size);
+        }
+        screen.borrow_mut().resize_callback = callback;
+
+        true
+    }
+
+    fn mouse_button_callback(screen: Rc<RefCell<Screen>>, button: Mouse, down: bool, p: (i32, i32)) -> bool {
+        let mouse_pos = screen.borrow().to_ui_coords(p);
+        let mods = unsafe { sdl2_sys::keyboard::SDL_GetModState() };
+
+        {
+            let mut screen_mut = screen.borrow_mut();
+            screen_mut.mouse_pos = mouse_pos;
+            screen_mut.modifiers = mods;
+            screen_mut.last_interaction = screen_mut.ticks();
+            screen_mut.mouse_button = if down { Some(button) } else { None };
+        }
+
+        let drop_path = find_child_path(&*screen.borrow(), mouse_pos);
+        if down {
+            screen.borrow().close_overlays_outside(&drop_path);
+        }
+        let drop_widget = drop_path.last().cloned();
+
+        // a drag released outside of the dragged widget still tells it about the release
+        let drag_widget = if screen.borrow().drag_active { screen.borrow().drag_widget.clone() } else { None };
+        if let Some(ref drag_val) = drag_widget {
+            let drop_id = drop_widget.as_ref().map(|x| x.borrow().id());
+            let drag_borrow = drag_val.borrow();
+            if !down && drop_id != Some(drag_borrow.id()) && drag_borrow.enabled_recursive() {
+                drag_borrow.mouse_button_event(to_parent_coords(&*drag_borrow, mouse_pos), button, false, mods);
+            }
+        }
+
+        {
+            let mut screen_mut = screen.borrow_mut();
+            if down {
+                screen_mut.drag_active = drop_widget.is_some();
+                screen_mut.drag_widget = drop_widget;
+            } else {
+                screen_mut.drag_active = false;
+                screen_mut.drag_widget = None;
+            }
+        }
+
+        let handled = screen.borrow().mouse_button_event(mouse_pos, button, down, mods);
+
+        // the end of a drag hands the cursor back to the widget under the mouse
+        Screen::update_cursor(screen.clone());
+        handled
+    }
+
+    fn mouse_motion_callback(screen: Rc<RefCell<Screen>>, p: (i32, i32), rel: (i32, i32)) -> bool {
+        let mouse_pos = screen.borrow().to_ui_coords(p);
+        let ui_scale = screen.borrow().ui_scale;
+        let rel = ((rel.0 as f32 / ui_scale).round() as i32, (rel.1 as f32 / ui_scale).round() as i32);
+        let mods = unsafe { sdl2_sys::keyboard::SDL_GetModState() };
+        let drag_widget: Option<Rc<RefCell<Widget>>>;
+        let button: Option<Mouse>;
+
+        {
+            let mut screen_mut = screen.borrow_mut();
+            screen_mut.mouse_pos = mouse_pos;
+            screen_mut.modifiers = mods;
+            screen_mut.last_interaction = screen_mut.ticks();
+            drag_widget = if screen_mut.drag_active { screen_mut.drag_widget.clone() } else { None };
+            button = screen_mut.mouse_button;
+        }
+
+        if let (Some(drag_val), Some(button_val)) = (drag_widget, button) {
+            let drag_borrow = drag_val.borrow();
+            if !drag_borrow.enabled_recursive() {
+                return false
+            }
+
+            let handled = drag_borrow.mouse_drag_event(to_parent_coords(&*drag_borrow, mouse_pos), rel, button_val, mods);
+            drop(drag_borrow);
+            Screen::update_hover(screen.clone(), Some(mouse_pos));
+            Screen::update_cursor(screen.clone());
+            return handled
+        }
+
+        let handled = screen.borrow().mouse_motion_event(mouse_pos, rel, button.unwrap_or(Mouse::Unknown(0)), mods);
+        Screen::update_hover(screen.clone(), Some(mouse_pos));
+        Screen::update_cursor(screen.clone());
+        handled
+    }
+
+    // Delivers enter/leave events along the path of widgets under `p`. `None`
+    // means the mouse left the window, so every hovered widget is left.
+    fn update_hover(screen: Rc<RefCell<Screen>>, p: Option<(u32, u32)>) {
+        let old_path = screen.borrow().hover_path.clone();
+        let (new_path, event_pos) = match p {
+            Some(val) => (find_child_path(&*screen.borrow(), val), val),
+            None => (Vec::new(), screen.borrow().mouse_pos)
+        };
+
+        update_hover_path(&old_path, &new_path, event_pos);
+        screen.borrow_mut().hover_path = new_path;
+    }
+
+    pub fn hover_path(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.hover_path.clone()
+    }
+
+    // Shows the cursor of the widget being dragged, or else of the widget under the mouse.
+    fn update_cursor(screen: Rc<RefCell<Screen>>) {
+        let target = {
+            let screen_borrow = screen.borrow();
+            match screen_borrow.drag_widget {
+                Some(ref val) if screen_borrow.drag_active => Some(val.clone()),
+                _ => find_child_path(&*screen_borrow, screen_borrow.mouse_pos).pop()
+            }
+        };
+
+        let cursor = match target {
+            Some(val) => val.borrow().cursor(),
+            None => Cursor::Arrow
+        };
+
+        screen.borrow_mut().cursor_manager.update(cursor);
+    }
+
+    fn scroll_callback(screen: Rc<RefCell<Screen>>, rel: (i32, i32)) -> bool {
+        let mouse_pos: (u32, u32);
+        {
+            let mut screen_mut = screen.borrow_mut();
+            screen_mut.last_interaction = screen_mut.ticks();
+            mouse_pos = screen_mut.mouse_pos;
+        }
+
+        let handled = screen.borrow().scroll_event(mouse_pos, rel);
+        handled
+    }
+
+    // The focus path gets the key first, from the focused widget outwards. Key
+    // presses none of them handle are matched against the shortcut registry.
+    // The modifiers come from the event, which has them as they were at the key
+    // press, rather than from the current keyboard state.
+    fn keyboard_callback(screen: Rc<RefCell<Screen>>, key: Mod, keycode: Option<Keycode>, scancode: Option<Scancode>, pressed: bool, repeat: bool) -> bool {
+        let mods = key.bits() as SDL_Keymod;
+        let focus_path: Vec<Rc<RefCell<Widget>>>;
+        {
+            let mut screen_mut = screen.borrow_mut();
+            screen_mut.modifiers = mods;
+            screen_mut.last_interaction = screen_mut.ticks();
+            focus_path = screen_mut.focussed_widgets.clone();
+        }
+
+        for widget in focus_path.iter().rev() {
+            let widget_borrow = widget.borrow();
+            if widget_borrow.focused() && widget_borrow.enabled_recursive() && widget_borrow.keyboard_event(key, scancode, pressed, mods) {
+                return true
+            }
+        }
+
+        if let (Some(keycode_val), true, false) = (keycode, pressed, repeat) {
+            let chord = KeyChord::from_keymod(mods, keycode_val);
+            let focus_ids: Vec<String> = focus_path.iter().map(|x| x.borrow().id()).collect();
+
+            // the shortcut is run once the screen is no longer borrowed, so its action
+            // is free to modify the screen, including the registry
+            let shortcut = screen.borrow().shortcuts.find(&chord, &focus_ids);
+            return match shortcut {
+                Some(val) => {
+                    val.run();
+                    true
+                },
+                None => false
+            }
+        }
+
+        false
+    }
+
+    // Committed text from the keyboard or an input method goes to the focus path,
+    // from the focused widget outwards.
+    fn text_input_callback(screen: Rc<RefCell<Screen>>, text: &str) -> bool {
+        let focus_path: Vec<Rc<RefCell<Widget>>>;
+        {
+            let mut screen_mut = screen.borrow_mut();
+            screen_mut.last_interaction = screen_mut.ticks();
+            focus_path = screen_mut.focussed_widgets.clone();
+        }
+
+        for widget in focus_path.iter().rev() {
+            let widget_borrow = widget.borrow();
+            if widget_borrow.focused() && widget_borrow.enabled_recursive() && widget_borrow.text_input_event(text) {
+                return true
+            }
+        }
+
+        false
+    }
+
+    // Composition (pre-edit) text of an input method. `start` is the cursor and
+    // `length` the selection within `text`; an empty text ends the composition.
+    fn text_editing_callback(screen: Rc<RefCell<Screen>>, text: &str, start: i32, length: i32) -> bool {
+        let focus_path: Vec<Rc<RefCell<Widget>>>;
+        {
+            let mut screen_mut = screen.borrow_mut();
+            screen_mut.last_interaction = screen_mut.ticks();
+            focus_path = screen_mut.focussed_widgets.clone();
+        }
+
+        for widget in focus_path.iter().rev() {
+            let widget_borrow = widget.borrow();
+            if widget_borrow.focused() && widget_borrow.enabled_recursive() && widget_borrow.text_editing_event(text, start, length) {
+                return true
+            }
+        }
+
+        false
     }
 }
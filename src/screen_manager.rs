@@ -0,0 +1,176 @@
+extern crate sdl2;
+
+use std::cell::RefCell;
+use std::cmp::min;
+use std::rc::Rc;
+use self::sdl2::event::Event;
+use screen::Screen;
+
+// nanovg fonts and images belong to a single context. The manager keeps a list
+// of everything that should be available on every screen and loads it into each
+// context in the same order, so font and image handles match between screens.
+enum SharedResource {
+    Font(String, String),
+    FontMem(String, &'static [u8]),
+    Image(String)
+}
+
+/// Owns the screens of an application with several SDL windows. Events are
+/// routed to the screen of the window they belong to and each screen's GL
+/// context is made current before it is drawn.
+pub struct ScreenManager {
+    screens: Vec<Rc<RefCell<Screen>>>,
+    resources: Vec<SharedResource>
+}
+
+impl ScreenManager {
+    pub fn new() -> ScreenManager {
+        ScreenManager {
+            screens: Vec::new(),
+            resources: Vec::new()
+        }
+    }
+
+    /// Adds a screen and loads the shared fonts and images into its context.
+    /// Leaves the screen's GL context current.
+    pub fn add_screen(&mut self, screen: Rc<RefCell<Screen>>) -> Result<(), String> {
+        let window_id = screen.borrow().window_id();
+        if self.screen(window_id).is_some() {
+            return Err(format!("A screen for window {} was already added", window_id))
+        }
+
+        for resource in &self.resources {
+            load_resource(&*screen.borrow(), resource)?;
+        }
+
+        self.screens.push(screen);
+        Ok(())
+    }
+
+    pub fn remove_screen(&mut self, window_id: u32) -> Option<Rc<RefCell<Screen>>> {
+        match self.screens.iter().position(|x| x.borrow().window_id() == window_id) {
+            Some(index) => Some(self.screens.remove(index)),
+            None => None
+        }
+    }
+
+    pub fn screen(&self, window_id: u32) -> Option<Rc<RefCell<Screen>>> {
+        self.screens.iter().find(|x| x.borrow().window_id() == window_id).cloned()
+    }
+
+    pub fn screens(&self) -> &Vec<Rc<RefCell<Screen>>> {
+        &self.screens
+    }
+
+    /// Loads a font from a file into every screen, and into screens added later.
+    pub fn add_font(&mut self, name: String, filename: String) -> Result<(), String> {
+        self.add_resource(SharedResource::Font(name, filename))
+    }
+
+    /// Loads a font from memory into every screen, and into screens added later.
+    /// nanovg does not copy the data, hence the static lifetime.
+    pub fn add_font_mem(&mut self, name: String, data: &'static [u8]) -> Result<(), String> {
+        self.add_resource(SharedResource::FontMem(name, data))
+    }
+
+    /// Loads an image into every screen, and into screens added later.
+    pub fn add_image(&mut self, filename: String) -> Result<(), String> {
+        self.add_resource(SharedResource::Image(filename))
+    }
+
+    fn add_resource(&mut self, resource: SharedResource) -> Result<(), String> {
+        for screen in &self.screens {
+            load_resource(&*screen.borrow(), &resource)?;
+        }
+
+        self.resources.push(resource);
+        Ok(())
+    }
+
+    /// Passes an event to the screen of the window it was sent to. Events that
+    /// do not belong to a window, like Quit, are left to the application.
+    pub fn handle_event(&self, event: &Event) -> bool {
+        let screen = match event_window_id(event) {
+            Some(val) => self.screen(val),
+            None => None
+        };
+
+        match screen {
+            Some(val) => Screen::handle_event(val, event),
+            None => false
+        }
+    }
+
+    /// Draws and swaps every screen that needs a redraw, switching to its GL
+    /// context first.
+    pub fn draw_all(&self) {
+        for screen in &self.screens {
+            let screen_borrow = screen.borrow();
+            if !screen_borrow.needs_redraw() || !screen_borrow.make_current() {
+                continue;
+            }
+
+            if screen_borrow.draw_all() {
+                screen_borrow.swap_window();
+            }
+        }
+    }
+
+    pub fn needs_redraw(&self) -> bool {
+        self.screens.iter().any(|x| x.borrow().needs_redraw())
+    }
+
+    /// The shortest redraw timeout of all screens, see Screen::redraw_timeout.
+    pub fn redraw_timeout(&self) -> Option<u32> {
+        let mut timeout: Option<u32> = None;
+        for screen in &self.screens {
+            if let Some(val) = screen.borrow().redraw_timeout() {
+                timeout = Some(match timeout {
+                    Some(current) => min(current, val),
+                    None => val
+                });
+            }
+        }
+
+        timeout
+    }
+}
+
+/// The id of the SDL window an event was sent to, or None for events that are
+/// not tied to a window.
+pub fn event_window_id(event: &Event) -> Option<u32> {
+    match *event {
+        Event::Window { window_id, .. } |
+        Event::KeyDown { window_id, .. } |
+        Event::KeyUp { window_id, .. } |
+        Event::TextEditing { window_id, .. } |
+        Event::TextInput { window_id, .. } |
+        Event::MouseMotion { window_id, .. } |
+        Event::MouseButtonDown { window_id, .. } |
+        Event::MouseButtonUp { window_id, .. } |
+        Event::MouseWheel { window_id, .. } => Some(window_id),
+        _ => None
+    }
+}
+
+fn load_resource(screen: &Screen, resource: &SharedResource) -> Result<(), String> {
+    if !screen.make_current() {
+        return Err(format!("Could not make the GL context of window {} current", screen.window_id()))
+    }
+
+    let nanovg_context = match screen.nanovg_context() {
+        Some(val) => val,
+        None => return Err(format!("Window {} has no nanovg context", screen.window_id()))
+    };
+    let loaded = match *resource {
+        SharedResource::Font(ref name, ref filename) => nanovg_context.create_font(name, filename).is_some(),
+        SharedResource::FontMem(ref name, data) => nanovg_context.create_font_mem(name, data).is_some(),
+        SharedResource::Image(ref filename) => nanovg_context.create_image(filename).is_some()
+    };
+
+    if loaded {
+        Ok(())
+    } else {
+        Err(format!("Could not load a shared resource into window {}", screen.window_id()))
+    }
+}
@@ -0,0 +1,616 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use std::cmp::max;
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use widget::{Widget, WidgetObj};
+use widget_container::{frame_time, request_redraw};
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+// space reserved for a scrollbar at the right or bottom edge
+const SCROLLBAR_SIZE: u32 = 12;
+const MIN_THUMB_SIZE: f32 = 16.0;
+
+// pixels one wheel notch scrolls
+const WHEEL_STEP: f32 = 40.0;
+
+// time constant of the smooth scrolling, in milliseconds
+const SMOOTHING_TIME: f32 = 60.0;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ScrollDirection {
+    Vertical,
+    Horizontal,
+    Both
+}
+
+/// Shows its first child at its preferred size and scrolls it when it does not
+/// fit. The child is clipped to the panel, and mouse events reach it moved by the
+/// scroll offset.
+pub struct ScrollPanel {
+    widget: WidgetObj,
+    direction: ScrollDirection,
+    content_size: Cell<(u32, u32)>,
+    viewport_size: Cell<(u32, u32)>,
+    offset: Cell<[f32; 2]>,
+    target: Cell<[f32; 2]>,
+    last_frame: Cell<Option<u32>>,
+    dragging: Cell<Option<usize>>
+}
+
+pub type VScrollPanel = ScrollPanel;
+
+impl Widget for ScrollPanel {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        let child = match self.widget.children.first() {
+            Some(val) => val.clone(),
+            None => return
+        };
+
+        let preferred = {
+            let child_borrow = child.borrow();
+            let preferred = child_borrow.preferred_size(nanovg_context);
+            let fixed = child_borrow.fixed_size();
+            (if fixed.0 > 0 { fixed.0 } else { preferred.0 }, if fixed.1 > 0 { fixed.1 } else { preferred.1 })
+        };
+
+        // a scrollbar takes space from the other direction
+        let (w, h) = self.widget.size;
+        let vertical_bar = self.scrolls(1) && preferred.1 > h;
+        let horizontal_bar = self.scrolls(0) && preferred.0 > w;
+        let viewport = (w.saturating_sub(if vertical_bar { SCROLLBAR_SIZE } else { 0 }),
+                        h.saturating_sub(if horizontal_bar { SCROLLBAR_SIZE } else { 0 }));
+        let content = (if self.scrolls(0) { max(preferred.0, viewport.0) } else { viewport.0 },
+                       if self.scrolls(1) { max(preferred.1, viewport.1) } else { viewport.1 });
+
+        {
+            let mut child_mut = child.borrow_mut();
+            child_mut.set_pos((0, 0));
+            child_mut.set_size(content);
+        }
+        self.content_size.set(content);
+        self.viewport_size.set(viewport);
+        self.offset.set(self.clamp(self.offset.get()));
+        self.target.set(self.clamp(self.target.get()));
+
+        child.borrow().perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        match self.widget.children.first() {
+            Some(val) => {
+                let preferred = val.borrow().preferred_size(nanovg_context);
+                (preferred.0 + if self.scrolls(1) { SCROLLBAR_SIZE } else { 0 },
+                 preferred.1 + if self.scrolls(0) { SCROLLBAR_SIZE } else { 0 })
+            },
+            None => self.widget.preferred_size(nanovg_context)
+        }
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        let child = match self.widget.children.first() {
+            Some(val) => val.clone(),
+            None => return
+        };
+
+        self.animate();
+
+        let viewport = self.viewport_size.get();
+        let offset = self.offset.get();
+
+        nanovg_context.save();
+        nanovg_context.intersect_scissor(0.0, 0.0, viewport.0 as f32, viewport.1 as f32);
+        let child_pos = child.borrow().pos();
+        nanovg_context.translate(child_pos.0 as f32 - offset[0].round(), child_pos.1 as f32 - offset[1].round());
+        child.borrow().draw(nanovg_context);
+        nanovg_context.restore();
+
+        for axis in 0..2 {
+            if self.max_scroll()[axis] > 0.0 {
+                self.draw_scrollbar(nanovg_context, axis);
+            }
+        }
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        let offset = self.offset.get();
+        (offset[0].round() as u32, offset[1].round() as u32)
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    // events
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        let local = self.to_local(p);
+
+        if button == Mouse::Left {
+            if down {
+                if let Some(axis) = self.scrollbar_at(local) {
+                    self.dragging.set(Some(axis));
+                    return true
+                }
+            } else if self.dragging.get().is_some() {
+                self.dragging.set(None);
+                return true
+            }
+        }
+
+        if let Some(child) = self.child_at(local) {
+            if child.borrow().mouse_button_event(self.to_child(local), button, down, mods) {
+                return true
+            }
+        }
+
+        if button == Mouse::Left && down && !self.widget.focused {
+            self.request_focus();
+        }
+
+        false
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        let local = self.to_local(p);
+        match self.widget.children.first() {
+            Some(child) if child.borrow().visible() && child.borrow().enabled() => {
+                child.borrow().mouse_motion_event(self.to_child(local), rel, button, mods)
+            },
+            _ => false
+        }
+    }
+
+    // Dragging the thumb moves the content by as much as the thumb moves relative
+    // to its track.
+    fn mouse_drag_event(&self, _: (u32, u32), rel: (i32, i32), _: Mouse, _: SDL_Keymod) -> bool {
+        let axis = match self.dragging.get() {
+            Some(val) => val,
+            None => return false
+        };
+
+        let (track, thumb) = self.thumb(axis);
+        if track <= thumb {
+            return true
+        }
+
+        let delta = (if axis == 0 { rel.0 } else { rel.1 }) as f32;
+        let mut offset = self.offset.get();
+        offset[axis] += delta * self.max_scroll()[axis] / (track - thumb);
+        let offset = self.clamp(offset);
+        self.offset.set(offset);
+        self.target.set(offset);
+        true
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    // The child under the mouse gets the wheel first, so nested scrollable
+    // widgets keep working.
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        let local = self.to_local(p);
+        if let Some(child) = self.child_at(local) {
+            if child.borrow().scroll_event(self.to_child(local), rel) {
+                return true
+            }
+        }
+
+        let max_scroll = self.max_scroll();
+        let mut target = self.target.get();
+        let mut handled = false;
+
+        if max_scroll[1] > 0.0 && rel.1 != 0 {
+            target[1] -= rel.1 as f32 * WHEEL_STEP;
+            handled = true;
+        }
+
+        // a panel that only scrolls horizontally uses the vertical wheel as well
+        let horizontal_rel = if rel.0 == 0 && self.direction == ScrollDirection::Horizontal { -rel.1 } else { rel.0 };
+        if max_scroll[0] > 0.0 && horizontal_rel != 0 {
+            target[0] += horizontal_rel as f32 * WHEEL_STEP;
+            handled = true;
+        }
+
+        self.target.set(self.clamp(target));
+        handled
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        self.widget.keyboard_event(key, scancode, pressed, mods)
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl ScrollPanel {
+    pub fn new(id: String) -> Rc<RefCell<ScrollPanel>> {
+        Rc::new(RefCell::new(ScrollPanel {
+            widget: WidgetObj::new(id),
+            direction: ScrollDirection::Vertical,
+            content_size: Cell::new((0, 0)),
+            viewport_size: Cell::new((0, 0)),
+            offset: Cell::new([0.0, 0.0]),
+            target: Cell::new([0.0, 0.0]),
+            last_frame: Cell::new(None),
+            dragging: Cell::new(None)
+        }))
+    }
+
+    impl_get_set!(direction, ScrollDirection);
+
+    /// The size of the child as of the last layout.
+    pub fn content_size(&self) -> (u32, u32) {
+        self.content_size.get()
+    }
+
+    /// The part of the panel the child is shown in, i.e. without the scrollbars.
+    pub fn viewport_size(&self) -> (u32, u32) {
+        self.viewport_size.get()
+    }
+
+    /// How far the child is scrolled right now, in pixels.
+    pub fn scroll(&self) -> (f32, f32) {
+        let offset = self.offset.get();
+        (offset[0], offset[1])
+    }
+
+    /// Where the smooth scrolling is heading.
+    pub fn scroll_target(&self) -> (f32, f32) {
+        let target = self.target.get();
+        (target[0], target[1])
+    }
+
+    /// Scrolls to `scroll` right away, without animating.
+    pub fn set_scroll(&mut self, scroll: (f32, f32)) {
+        let offset = self.clamp([scroll.0, scroll.1]);
+        self.offset.set(offset);
+        self.target.set(offset);
+    }
+
+    fn scrolls(&self, axis: usize) -> bool {
+        match self.direction {
+            ScrollDirection::Vertical => axis == 1,
+            ScrollDirection::Horizontal => axis == 0,
+            ScrollDirection::Both => true
+        }
+    }
+
+    fn max_scroll(&self) -> [f32; 2] {
+        let (content, viewport) = (self.content_size.get(), self.viewport_size.get());
+        [content.0.saturating_sub(viewport.0) as f32, content.1.saturating_sub(viewport.1) as f32]
+    }
+
+    fn clamp(&self, offset: [f32; 2]) -> [f32; 2] {
+        let max_scroll = self.max_scroll();
+        [offset[0].max(0.0).min(max_scroll[0]), offset[1].max(0.0).min(max_scroll[1])]
+    }
+
+    // Moves the offset towards the target, asking for more frames until it gets
+    // there. Without a screen there is no clock, so it jumps.
+    fn animate(&self) {
+        let (offset, target) = (self.offset.get(), self.target.get());
+        let now = frame_time(self);
+        let last_frame = self.last_frame.get();
+        self.last_frame.set(now);
+
+        if offset == target {
+            return
+        }
+
+        let progress = match (now, last_frame) {
+            (Some(now_val), Some(last_val)) => 1.0 - (-(now_val.saturating_sub(last_val) as f32) / SMOOTHING_TIME).exp(),
+            (Some(_), None) => 0.25,
+            _ => 1.0
+        };
+
+        let mut new_offset = [0.0; 2];
+        for axis in 0..2 {
+            new_offset[axis] = offset[axis] + (target[axis] - offset[axis]) * progress;
+            if (target[axis] - new_offset[axis]).abs() < 0.5 {
+                new_offset[axis] = target[axis];
+            }
+        }
+        self.offset.set(new_offset);
+
+        if new_offset != target {
+            request_redraw(self);
+        }
+    }
+
+    // `local` is relative to the panel.
+    // The release of a drag arrives even when the mouse left the panel, so points
+    // above or left of it are clamped to its edge.
+    fn to_local(&self, p: (u32, u32)) -> (u32, u32) {
+        (p.0.saturating_sub(self.widget.pos.0), p.1.saturating_sub(self.widget.pos.1))
+    }
+
+    fn to_child(&self, local: (u32, u32)) -> (u32, u32) {
+        let offset = self.scroll_offset();
+        (local.0 + offset.0, local.1 + offset.1)
+    }
+
+    fn child_at(&self, local: (u32, u32)) -> Option<Rc<RefCell<Widget>>> {
+        let viewport = self.viewport_size.get();
+        if local.0 >= viewport.0 || local.1 >= viewport.1 {
+            return None
+        }
+
+        match self.widget.children.first() {
+            Some(val) => {
+                let child = val.borrow();
+                if child.visible() && child.enabled() && child.contains(self.to_child(local)) {
+                    Some(val.clone())
+                } else {
+                    None
+                }
+            },
+            None => None
+        }
+    }
+
+    // The axis of the scrollbar at `local`, if any.
+    fn scrollbar_at(&self, local: (u32, u32)) -> Option<usize> {
+        let viewport = self.viewport_size.get();
+        let max_scroll = self.max_scroll();
+        if max_scroll[1] > 0.0 && local.0 >= viewport.0 && local.1 < viewport.1 {
+            Some(1)
+        } else if max_scroll[0] > 0.0 && local.1 >= viewport.1 && local.0 < viewport.0 {
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    // The length of the track of a scrollbar and of its thumb.
+    fn thumb(&self, axis: usize) -> (f32, f32) {
+        let (content, viewport) = (self.content_size.get(), self.viewport_size.get());
+        let (content, viewport) = if axis == 0 { (content.0, viewport.0) } else { (content.1, viewport.1) };
+        let track = viewport as f32 - 8.0;
+        let thumb = if content > 0 { track * viewport as f32 / content as f32 } else { track };
+        (track, thumb.max(MIN_THUMB_SIZE).min(track))
+    }
+
+    fn draw_scrollbar(&self, nanovg_context: &nanovg::Context, axis: usize) {
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let viewport = self.viewport_size.get();
+        let (track, thumb) = self.thumb(axis);
+        let thumb_pos = 4.0 + (track - thumb) * self.offset.get()[axis] / self.max_scroll()[axis];
+
+        // (x, y, width, height) of the track and of the thumb
+        let (track_rect, thumb_rect) = if axis == 1 {
+            let bar_x = viewport.0 as f32 + 2.0;
+            ((bar_x, 4.0, 8.0, track), (bar_x, thumb_pos, 8.0, thumb))
+        } else {
+            let bar_y = viewport.1 as f32 + 2.0;
+            ((4.0, bar_y, track, 8.0), (thumb_pos, bar_y, thumb, 8.0))
+        };
+
+        let track_paint = nanovg_context.box_gradient(track_rect.0 + 1.0, track_rect.1 + 1.0, track_rect.2, track_rect.3, 3.0, 4.0,
+            theme.scrollbar_track_inner().to_nanovg_color(), theme.scrollbar_track_outer().to_nanovg_color());
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(track_rect.0, track_rect.1, track_rect.2, track_rect.3, 3.0);
+        nanovg_context.fill_paint(track_paint);
+        nanovg_context.fill();
+
+        let thumb_paint = nanovg_context.box_gradient(thumb_rect.0 - 1.0, thumb_rect.1 - 1.0, thumb_rect.2, thumb_rect.3, 3.0, 4.0,
+            theme.scrollbar_thumb_inner().to_nanovg_color(), theme.scrollbar_thumb_outer().to_nanovg_color());
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(thumb_rect.0 + 1.0, thumb_rect.1 + 1.0, thumb_rect.2 - 2.0, thumb_rect.3 - 2.0, 2.0);
+        nanovg_context.fill_paint(thumb_paint);
+        nanovg_context.fill();
+    }
+}
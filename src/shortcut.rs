@@ -0,0 +1,228 @@
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+use self::sdl2::keyboard::Keycode;
+use self::sdl2_sys::keycode::SDL_Keymod;
+
+// SDL_Keymod masks covering both the left and right variant of each modifier.
+pub const KMOD_CTRL: u32 = 0x0040 | 0x0080;
+pub const KMOD_SHIFT: u32 = 0x0001 | 0x0002;
+pub const KMOD_ALT: u32 = 0x0100 | 0x0200;
+pub const KMOD_GUI: u32 = 0x0400 | 0x0800;
+
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub struct KeyChord {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub gui: bool,
+    pub key: Keycode
+}
+
+impl KeyChord {
+    pub fn new(key: Keycode) -> KeyChord {
+        KeyChord {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            gui: false,
+            key: key
+        }
+    }
+
+    pub fn from_keymod(mods: SDL_Keymod, key: Keycode) -> KeyChord {
+        let mods = mods as u32;
+        KeyChord {
+            ctrl: mods & KMOD_CTRL != 0,
+            shift: mods & KMOD_SHIFT != 0,
+            alt: mods & KMOD_ALT != 0,
+            gui: mods & KMOD_GUI != 0,
+            key: key
+        }
+    }
+}
+
+// Chords are written as modifiers followed by the SDL key name, joined by '+',
+// e.g. "Ctrl+Shift+S". Parsing is case-insensitive for the modifiers.
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<KeyChord, String> {
+        let (modifiers, key_name) = if s.ends_with("++") {
+            (&s[..s.len() - 2], "+")
+        } else {
+            match s.rfind('+') {
+                Some(index) => (&s[..index], &s[index + 1..]),
+                None => ("", s)
+            }
+        };
+
+        let key = match Keycode::from_name(key_name.trim()) {
+            Some(val) => val,
+            None => return Err(format!("Unknown key \"{}\" in shortcut \"{}\"", key_name, s))
+        };
+
+        let mut chord = KeyChord::new(key);
+        for modifier in modifiers.split('+').map(|x| x.trim()).filter(|x| x.len() > 0) {
+            match &*modifier.to_lowercase() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" => chord.alt = true,
+                "gui" | "cmd" | "super" => chord.gui = true,
+                _ => return Err(format!("Unknown modifier \"{}\" in shortcut \"{}\"", modifier, s))
+            }
+        }
+
+        Ok(chord)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.gui {
+            write!(f, "Gui+")?;
+        }
+        write!(f, "{}", self.key.name())
+    }
+}
+
+impl fmt::Debug for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "KeyChord {}", self)
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ShortcutScope {
+    // active everywhere on the screen
+    Global,
+    // active while the widget with this id is on the focus path. The id is meant
+    // to be a window's, but it is not checked to be one: any widget on the path
+    // matches, ranked by its depth like a window
+    Window(String),
+    // active while the widget with this id or one of its descendants has focus
+    FocusedWithin(String)
+}
+
+pub struct Shortcut {
+    chord: KeyChord,
+    scope: ShortcutScope,
+    action: Box<Fn()>
+}
+
+impl Shortcut {
+    pub fn chord(&self) -> KeyChord {
+        self.chord
+    }
+
+    pub fn scope(&self) -> &ShortcutScope {
+        &self.scope
+    }
+
+    pub fn run(&self) {
+        (self.action)()
+    }
+}
+
+pub struct ShortcutRegistry {
+    shortcuts: Vec<Rc<Shortcut>>
+}
+
+impl ShortcutRegistry {
+    pub fn new() -> ShortcutRegistry {
+        ShortcutRegistry {
+            shortcuts: Vec::new()
+        }
+    }
+
+    /// Registers `action` for `chord` within `scope`. A chord can be bound once per
+    /// scope; registering it again in the same scope is a conflict and fails.
+    pub fn register(&mut self, chord: KeyChord, scope: ShortcutScope, action: Box<Fn()>) -> Result<(), String> {
+        if self.conflicts(&chord, &scope) {
+            return Err(format!("Shortcut {} is already registered for {:?}", chord, scope));
+        }
+
+        self.shortcuts.push(Rc::new(Shortcut {
+            chord: chord,
+            scope: scope,
+            action: action
+        }));
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, chord: &KeyChord, scope: &ShortcutScope) -> bool {
+        match self.shortcuts.iter().position(|x| x.chord == *chord && x.scope == *scope) {
+            Some(index) => {
+                self.shortcuts.remove(index);
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn conflicts(&self, chord: &KeyChord, scope: &ShortcutScope) -> bool {
+        self.shortcuts.iter().any(|x| x.chord == *chord && x.scope == *scope)
+    }
+
+    pub fn shortcuts(&self) -> &Vec<Rc<Shortcut>> {
+        &self.shortcuts
+    }
+
+    /// Finds the most specific shortcut for `chord` given the ids of the focus path,
+    /// outermost first. FocusedWithin beats Window, which beats Global; between two
+    /// scoped shortcuts the one closer to the focused widget wins.
+    ///
+    /// The shortcut is shared with the registry, so it can be run after the
+    /// registry is no longer borrowed and its action may change the registry.
+    pub fn find(&self, chord: &KeyChord, focus_path: &[String]) -> Option<Rc<Shortcut>> {
+        let mut best: Option<(&Rc<Shortcut>, usize)> = None;
+
+        for shortcut in self.shortcuts.iter().filter(|x| x.chord == *chord) {
+            let rank = match shortcut.scope {
+                ShortcutScope::Global => Some(0),
+                ShortcutScope::Window(ref id) => {
+                    focus_path.iter().position(|x| x == id).map(|x| 1 + x)
+                },
+                ShortcutScope::FocusedWithin(ref id) => {
+                    focus_path.iter().position(|x| x == id).map(|x| 1 + focus_path.len() + x)
+                }
+            };
+
+            if let Some(rank_val) = rank {
+                let better = match best {
+                    Some((_, best_rank)) => rank_val > best_rank,
+                    None => true
+                };
+
+                if better {
+                    best = Some((shortcut, rank_val));
+                }
+            }
+        }
+
+        best.map(|x| x.0.clone())
+    }
+
+    /// Runs the shortcut `find` returns, if any. Returns whether one was run.
+    pub fn trigger(&self, chord: &KeyChord, focus_path: &[String]) -> bool {
+        match self.find(chord, focus_path) {
+            Some(shortcut) => {
+                shortcut.run();
+                true
+            },
+            None => false
+        }
+    }
+}
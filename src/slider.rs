@@ -0,0 +1,497 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::{Color, Cursor};
+use widget::{Widget, WidgetObj};
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+// shadow drawn around the knob, the track is inset by it on both ends
+const KNOB_SHADOW: f32 = 3.0;
+
+// arrow keys move by one step, or by this fraction of the range without steps
+const KEY_INCREMENT: f32 = 0.01;
+
+pub struct Slider {
+    widget: WidgetObj,
+    value: Cell<f32>,
+    range: (f32, f32),
+    step: Option<f32>,
+    highlighted_range: Option<(f32, f32)>,
+    highlight_color: Color,
+    on_change: Option<Box<Fn(f32)>>,
+    on_final_change: Option<Box<Fn(f32)>>
+}
+
+impl Widget for Slider {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, _: &nanovg::Context) -> (u32, u32) {
+        let fixed = self.widget.fixed_size;
+        (if fixed.0 > 0 { fixed.0 } else { 70 },
+         if fixed.1 > 0 { fixed.1 } else { 16 })
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        self.widget.draw(nanovg_context);
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let h = self.widget.size.1 as f32;
+        let center_y = h / 2.0;
+        let knob_radius = (h * 0.4).floor();
+        let (start_x, width_x) = self.track();
+        let knob_x = start_x + self.fraction(self.value.get()) * width_x;
+        let knob_y = center_y + 0.5;
+        let enabled = self.widget.enabled_recursive();
+
+        let background = nanovg_context.box_gradient(start_x, center_y - 3.0 + 1.0, width_x, 6.0, 3.0, 3.0,
+            nanovg::Color::rgba(0, 0, 0, if enabled { 32 } else { 10 }),
+            nanovg::Color::rgba(0, 0, 0, if enabled { 128 } else { 210 }));
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(start_x, center_y - 3.0 + 1.0, width_x, 6.0, 2.0);
+        nanovg_context.fill_paint(background);
+        nanovg_context.fill();
+
+        if let Some((low, high)) = self.highlighted_range {
+            let (low, high) = (self.fraction(low), self.fraction(high));
+            if high > low {
+                nanovg_context.begin_path();
+                nanovg_context.rounded_rect(start_x + low * width_x, center_y - KNOB_SHADOW + 1.0,
+                    width_x * (high - low), KNOB_SHADOW * 2.0, 2.0);
+                nanovg_context.fill_color(self.highlight_color.to_nanovg_color());
+                nanovg_context.fill();
+            }
+        }
+
+        let knob_shadow = nanovg_context.radial_gradient(knob_x, knob_y, knob_radius - KNOB_SHADOW,
+            knob_radius + KNOB_SHADOW, nanovg::Color::rgba(0, 0, 0, 64), theme.transparent().to_nanovg_color());
+        nanovg_context.begin_path();
+        nanovg_context.circle(knob_x, knob_y, knob_radius + KNOB_SHADOW);
+        nanovg_context.fill_paint(knob_shadow);
+        nanovg_context.fill();
+
+        let knob = nanovg_context.linear_gradient(0.0, center_y - knob_radius, 0.0, center_y + knob_radius,
+            theme.border_light().to_nanovg_color(), theme.border_medium().to_nanovg_color());
+        let knob_reverse = nanovg_context.linear_gradient(0.0, center_y - knob_radius, 0.0, center_y + knob_radius,
+            theme.border_medium().to_nanovg_color(), theme.border_light().to_nanovg_color());
+
+        nanovg_context.begin_path();
+        nanovg_context.circle(knob_x, knob_y, knob_radius);
+        nanovg_context.stroke_color(theme.border_dark().to_nanovg_color());
+        nanovg_context.fill_paint(knob);
+        nanovg_context.stroke();
+        nanovg_context.fill();
+
+        nanovg_context.begin_path();
+        nanovg_context.circle(knob_x, knob_y, knob_radius / 2.0);
+        nanovg_context.fill_color(nanovg::Color::rgba(150, 150, 150, if enabled { 255 } else { 100 }));
+        nanovg_context.stroke_paint(knob_reverse);
+        nanovg_context.stroke();
+        nanovg_context.fill();
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    // events
+
+    // Clicking anywhere on the track jumps there; on_final_change fires on release.
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        if button != Mouse::Left || !self.widget.enabled_recursive() {
+            return self.widget.mouse_button_event(p, button, down, mods)
+        }
+
+        if down && !self.widget.focused {
+            self.request_focus();
+        }
+
+        self.move_to(p.0);
+        if !down {
+            if let Some(ref val) = self.on_final_change {
+                val(self.value.get());
+            }
+        }
+
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        if button != Mouse::Left || !self.widget.enabled_recursive() {
+            return self.widget.mouse_drag_event(p, rel, button, mods)
+        }
+
+        self.move_to(p.0);
+        true
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        self.widget.scroll_event(p, rel)
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        if !self.widget.focused || !self.widget.enabled_recursive() {
+            return self.widget.keyboard_event(key, scancode, pressed, mods)
+        }
+
+        let direction = match scancode {
+            Some(Scancode::Left) | Some(Scancode::Down) => -1.0,
+            Some(Scancode::Right) | Some(Scancode::Up) => 1.0,
+            _ => return self.widget.keyboard_event(key, scancode, pressed, mods)
+        };
+
+        if pressed {
+            let increment = match self.step {
+                Some(val) => val,
+                None => (self.range.1 - self.range.0) * KEY_INCREMENT
+            };
+            let old_value = self.value.get();
+            self.change_value(old_value + direction * increment);
+            if self.value.get() != old_value {
+                if let Some(ref val) = self.on_final_change {
+                    val(self.value.get());
+                }
+            }
+        }
+
+        true
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl Slider {
+    pub fn new(id: String) -> Rc<RefCell<Slider>> {
+        Rc::new(RefCell::new(Slider {
+            widget: WidgetObj::new(id),
+            value: Cell::new(0.0),
+            range: (0.0, 1.0),
+            step: None,
+            highlighted_range: None,
+            highlight_color: Color { r: 255.0, g: 80.0, b: 80.0, a: 70.0 },
+            on_change: None,
+            on_final_change: None
+        }))
+    }
+
+    impl_get_set!(highlight_color, Color);
+
+    pub fn value(&self) -> f32 {
+        self.value.get()
+    }
+
+    /// Sets the value, clamped to the range and snapped to the steps, without
+    /// calling the callbacks.
+    pub fn set_value(&mut self, value: f32) {
+        let value = self.constrain(value);
+        self.value.set(value);
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        self.range
+    }
+
+    pub fn set_range(&mut self, range: (f32, f32)) {
+        self.range = range;
+        let value = self.constrain(self.value.get());
+        self.value.set(value);
+    }
+
+    pub fn step(&self) -> Option<f32> {
+        self.step
+    }
+
+    /// With a step the value snaps to the range start plus multiples of it.
+    pub fn set_step(&mut self, step: Option<f32>) {
+        self.step = step;
+        let value = self.constrain(self.value.get());
+        self.value.set(value);
+    }
+
+    pub fn highlighted_range(&self) -> Option<(f32, f32)> {
+        self.highlighted_range
+    }
+
+    /// Part of the track drawn in the highlight color, in the same units as the value.
+    pub fn set_highlighted_range(&mut self, range: Option<(f32, f32)>) {
+        self.highlighted_range = range;
+    }
+
+    /// Called for every change while the user drags the knob.
+    pub fn set_on_change(&mut self, callback: Option<Box<Fn(f32)>>) {
+        self.on_change = callback;
+    }
+
+    /// Called once the user releases the knob, or after a key press.
+    pub fn set_on_final_change(&mut self, callback: Option<Box<Fn(f32)>>) {
+        self.on_final_change = callback;
+    }
+
+    // The start and width of the track relative to the widget.
+    fn track(&self) -> (f32, f32) {
+        let inset = (self.widget.size.1 as f32 * 0.4).floor() + KNOB_SHADOW;
+        let width = self.widget.size.0 as f32 - 2.0 * inset;
+        (inset, if width > 1.0 { width } else { 1.0 })
+    }
+
+    fn fraction(&self, value: f32) -> f32 {
+        let span = self.range.1 - self.range.0;
+        if span <= 0.0 {
+            return 0.0
+        }
+
+        let fraction = (value - self.range.0) / span;
+        fraction.max(0.0).min(1.0)
+    }
+
+    fn constrain(&self, value: f32) -> f32 {
+        let value = match self.step {
+            Some(step) if step > 0.0 => self.range.0 + ((value - self.range.0) / step).round() * step,
+            _ => value
+        };
+        value.max(self.range.0).min(self.range.1)
+    }
+
+    // `x` is in parent coordinates, like the mouse events.
+    fn move_to(&self, x: u32) {
+        let (start_x, width_x) = self.track();
+        let local_x = x as f32 - self.widget.pos.0 as f32 - start_x;
+        let fraction = (local_x / width_x).max(0.0).min(1.0);
+        self.change_value(self.range.0 + fraction * (self.range.1 - self.range.0));
+    }
+
+    fn change_value(&self, value: f32) {
+        let value = self.constrain(value);
+        if value != self.value.get() {
+            self.value.set(value);
+            if let Some(ref val) = self.on_change {
+                val(value);
+            }
+        }
+    }
+}
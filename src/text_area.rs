@@ -0,0 +1,1168 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use shortcut::{KMOD_ALT, KMOD_CTRL, KMOD_GUI, KMOD_SHIFT};
+use text_buffer::{UndoHistory, word_end, word_start};
+use widget::{Widget, WidgetObj};
+use widget_container::{clipboard_text, set_clipboard_text};
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+const PADDING: f32 = 5.0;
+const SCROLLBAR_WIDTH: f32 = 12.0;
+const LINE_HEIGHT: f32 = 1.25;
+const SCROLL_ROWS: i32 = 3;
+
+// (line, column) with the column counted in chars
+pub type TextPos = (usize, usize);
+
+// The row starts used for lines that were not measured yet.
+const SINGLE_ROW: &'static [usize] = &[0];
+
+// Undo entries record what an edit replaced, so undoing a keystroke in a long
+// document does not have to copy the whole text.
+struct Edit {
+    start: TextPos,
+    removed: String,
+    inserted: String,
+    cursor_before: TextPos
+}
+
+struct Document {
+    lines: Vec<String>,
+    // the columns each wrapped row of a line starts at; None until the line is measured
+    rows: Vec<Option<Vec<usize>>>,
+    cursor: TextPos,
+    anchor: Option<TextPos>,
+    history: UndoHistory<Edit>
+}
+
+impl Document {
+    fn new(text: &str) -> Document {
+        let lines: Vec<String> = split_lines(text);
+        Document {
+            rows: vec![None; lines.len()],
+            lines: lines,
+            cursor: (0, 0),
+            anchor: None,
+            history: UndoHistory::new()
+        }
+    }
+
+    fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line_len(&self, line: usize) -> usize {
+        self.lines[line].chars().count()
+    }
+
+    fn end(&self) -> TextPos {
+        let last = self.lines.len() - 1;
+        (last, self.line_len(last))
+    }
+
+    fn clamp(&self, pos: TextPos) -> TextPos {
+        if pos.0 >= self.lines.len() {
+            return self.end()
+        }
+        (pos.0, pos.1.min(self.line_len(pos.0)))
+    }
+
+    fn set_cursor(&mut self, pos: TextPos, extend: bool) {
+        if extend {
+            if self.anchor.is_none() {
+                self.anchor = Some(self.cursor);
+            }
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = self.clamp(pos);
+    }
+
+    fn selection(&self) -> Option<(TextPos, TextPos)> {
+        match self.anchor {
+            Some(val) if val < self.cursor => Some((val, self.cursor)),
+            Some(val) if val > self.cursor => Some((self.cursor, val)),
+            _ => None
+        }
+    }
+
+    fn range_text(&self, start: TextPos, end: TextPos) -> String {
+        if start.0 == end.0 {
+            return self.lines[start.0].chars().skip(start.1).take(end.1 - start.1).collect()
+        }
+
+        let mut text: String = self.lines[start.0].chars().skip(start.1).collect();
+        for line in &self.lines[start.0 + 1..end.0] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.extend(self.lines[end.0].chars().take(end.1));
+        text
+    }
+
+    // Replaces the text between start and end and returns the end of the new text.
+    fn replace(&mut self, start: TextPos, end: TextPos, text: &str) -> TextPos {
+        let prefix: String = self.lines[start.0].chars().take(start.1).collect();
+        let suffix: String = self.lines[end.0].chars().skip(end.1).collect();
+        let new_lines = split_lines(&(prefix + text + &suffix));
+        let count = new_lines.len();
+
+        self.lines.splice(start.0..end.0 + 1, new_lines);
+        self.rows.splice(start.0..end.0 + 1, vec![None; count]);
+        end_of(start, text)
+    }
+
+    fn edit(&mut self, start: TextPos, end: TextPos, text: &str) {
+        let removed = self.range_text(start, end);
+        self.history.push(Edit {
+            start: start,
+            removed: removed,
+            inserted: text.to_string(),
+            cursor_before: self.cursor
+        });
+        self.cursor = self.replace(start, end, text);
+        self.anchor = None;
+    }
+
+    fn insert(&mut self, text: &str) {
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+        self.edit(start, end, text);
+    }
+
+    fn delete_backward(&mut self, word: bool) -> bool {
+        let (start, end) = match self.selection() {
+            Some(val) => val,
+            None if self.cursor == (0, 0) => return false,
+            None => (self.position_left(self.cursor, word), self.cursor)
+        };
+        self.edit(start, end, "");
+        true
+    }
+
+    fn delete_forward(&mut self, word: bool) -> bool {
+        let (start, end) = match self.selection() {
+            Some(val) => val,
+            None if self.cursor == self.end() => return false,
+            None => (self.cursor, self.position_right(self.cursor, word))
+        };
+        self.edit(start, end, "");
+        true
+    }
+
+    fn undo(&mut self) -> bool {
+        match self.history.undo() {
+            Some(edit) => {
+                let end = end_of(edit.start, &edit.inserted);
+                self.replace(edit.start, end, &edit.removed);
+                self.cursor = edit.cursor_before;
+                self.anchor = None;
+                self.history.undone(edit);
+                true
+            },
+            None => false
+        }
+    }
+
+    fn redo(&mut self) -> bool {
+        match self.history.redo() {
+            Some(edit) => {
+                let end = end_of(edit.start, &edit.removed);
+                self.cursor = self.replace(edit.start, end, &edit.inserted);
+                self.anchor = None;
+                self.history.redone(edit);
+                true
+            },
+            None => false
+        }
+    }
+
+    // Moving left from the start of a line continues at the end of the previous
+    // one. Within a line words end where they do in a TextBox.
+    fn position_left(&self, pos: TextPos, word: bool) -> TextPos {
+        if pos.1 == 0 {
+            return if pos.0 > 0 { (pos.0 - 1, self.line_len(pos.0 - 1)) } else { pos }
+        }
+        if !word {
+            return (pos.0, pos.1 - 1)
+        }
+        (pos.0, word_start(&self.lines[pos.0], pos.1))
+    }
+
+    fn position_right(&self, pos: TextPos, word: bool) -> TextPos {
+        if pos.1 >= self.line_len(pos.0) {
+            return if pos.0 + 1 < self.lines.len() { (pos.0 + 1, 0) } else { pos }
+        }
+        if !word {
+            return (pos.0, pos.1 + 1)
+        }
+        (pos.0, word_end(&self.lines[pos.0], pos.1))
+    }
+
+    fn row_starts(&self, line: usize) -> &[usize] {
+        match self.rows[line] {
+            Some(ref val) => val,
+            None => SINGLE_ROW
+        }
+    }
+
+    // The first and one-past-last column of a wrapped row.
+    fn row_range(&self, line: usize, row: usize) -> (usize, usize) {
+        let starts = self.row_starts(line);
+        let end = if row + 1 < starts.len() { starts[row + 1] } else { self.line_len(line) };
+        (starts[row], end)
+    }
+
+    fn row_of(&self, pos: TextPos) -> usize {
+        let starts = self.row_starts(pos.0);
+        starts.iter().rposition(|x| *x <= pos.1).unwrap_or(0)
+    }
+
+    // Moves by wrapped rows, keeping the column within the row where possible.
+    fn position_vertical(&self, pos: TextPos, rows: i32) -> TextPos {
+        let (mut line, mut row) = (pos.0, self.row_of(pos));
+        let offset = pos.1 - self.row_range(line, row).0;
+
+        for _ in 0..rows.abs() {
+            if rows < 0 {
+                if row > 0 {
+                    row -= 1;
+                } else if line > 0 {
+                    line -= 1;
+                    row = self.row_starts(line).len() - 1;
+                } else {
+                    return (0, 0)
+                }
+            } else {
+                if row + 1 < self.row_starts(line).len() {
+                    row += 1;
+                } else if line + 1 < self.lines.len() {
+                    line += 1;
+                    row = 0;
+                } else {
+                    return self.end()
+                }
+            }
+        }
+
+        (line, self.row_column(line, row, offset))
+    }
+
+    // A column `offset` chars into a row, kept inside the row so the caret does
+    // not jump to the next one.
+    fn row_column(&self, line: usize, row: usize, offset: usize) -> usize {
+        let (start, end) = self.row_range(line, row);
+        let last_row = row + 1 == self.row_starts(line).len();
+        let max = if last_row || end == start { end } else { end - 1 };
+        start.saturating_add(offset).min(max)
+    }
+
+    fn row_count(&self) -> usize {
+        (0..self.lines.len()).map(|index| self.row_starts(index).len()).sum()
+    }
+
+    // Rows above `line`, using a single row for lines that were not measured yet.
+    fn rows_before(&self, line: usize) -> usize {
+        (0..line).map(|index| self.row_starts(index).len()).sum()
+    }
+}
+
+fn split_lines(text: &str) -> Vec<String> {
+    text.split('\n').map(|x| x.trim_right_matches('\r').to_string()).collect()
+}
+
+fn end_of(start: TextPos, text: &str) -> TextPos {
+    match text.rfind('\n') {
+        Some(index) => (start.0 + text.matches('\n').count(), text[index + 1..].chars().count()),
+        None => (start.0, start.1 + text.chars().count())
+    }
+}
+
+// A row as it was drawn last: where it is in the document, its top and the x of
+// every caret position in it, both in widget coordinates.
+struct DrawnRow {
+    line: usize,
+    row: usize,
+    start: usize,
+    top: f32,
+    carets: Vec<f32>
+}
+
+// An editable multi-line text with optional word wrap and line numbers. Only the
+// lines in view are measured and drawn; lines that were never on screen count as
+// one row until they are.
+pub struct TextArea {
+    widget: WidgetObj,
+    document: RefCell<Document>,
+    editable: bool,
+    word_wrap: bool,
+    line_numbers: bool,
+    modified: Cell<bool>,
+    scroll: Cell<f32>,
+    scroll_to_cursor: Cell<bool>,
+    // width and font size the cached rows were wrapped for
+    wrap_key: Cell<(f32, u32)>,
+    // mouse positions are mapped to text positions with the rows that were drawn last
+    drawn_rows: RefCell<Vec<DrawnRow>>,
+    // pre-edit text of an input method with its cursor and selection
+    composition: RefCell<(String, i32, i32)>,
+    // the caret as last drawn, for the candidate window of input methods
+    caret_rect: Cell<Option<(f32, f32, f32, f32)>>,
+    mouse_selecting: Cell<bool>,
+    on_change: Option<Box<Fn(&str)>>
+}
+
+impl Widget for TextArea {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, _: &nanovg::Context) -> (u32, u32) {
+        let fixed = self.widget.fixed_size;
+        let row_height = self.row_height();
+        (if fixed.0 > 0 { fixed.0 } else { 300 },
+         if fixed.1 > 0 { fixed.1 } else { (row_height * 8.0 + 2.0 * PADDING) as u32 })
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        self.widget.draw(nanovg_context);
+        self.caret_rect.set(None);
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let (w, h) = (self.widget.size.0 as f32, self.widget.size.1 as f32);
+        let editing = self.widget.focused;
+        let enabled = self.widget.enabled_recursive();
+        let font_size = self.font_size() as f32;
+        let row_height = self.row_height();
+
+        let background = nanovg_context.box_gradient(1.0, 2.0, w - 2.0, h - 2.0, 3.0, 4.0,
+            nanovg::Color::rgba(255, 255, 255, if editing { 24 } else { 32 }), nanovg::Color::rgba(32, 32, 32, 32));
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(1.0, 2.0, w - 2.0, h - 2.0, 3.0);
+        nanovg_context.fill_paint(background);
+        nanovg_context.fill();
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(0.5, 0.5, w - 1.0, h - 1.0, 2.5);
+        nanovg_context.stroke_color(nanovg::Color::rgba(0, 0, 0, 48));
+        nanovg_context.stroke();
+
+        nanovg_context.font_face(&self.widget.font());
+        nanovg_context.font_size(font_size);
+        nanovg_context.text_align(nanovg::LEFT | nanovg::TOP);
+
+        let mut document = self.document.borrow_mut();
+
+        let gutter = if self.line_numbers {
+            nanovg_context.text_bounds(0.0, 0.0, &format!("{}", document.lines.len()), None) + 2.0 * PADDING
+        } else {
+            0.0
+        };
+        let text_x = PADDING + gutter;
+        let text_width = w - 2.0 * PADDING - gutter - SCROLLBAR_WIDTH;
+        let view_height = h - 2.0 * PADDING;
+
+        // a new width or font size invalidates every wrapped line
+        let wrap_key = (if self.word_wrap { text_width } else { 0.0 }, self.font_size());
+        if self.wrap_key.get() != wrap_key {
+            self.wrap_key.set(wrap_key);
+            for row in document.rows.iter_mut() {
+                *row = None;
+            }
+        }
+
+        if self.scroll_to_cursor.get() {
+            self.scroll_to_cursor.set(false);
+            let line = document.cursor.0;
+            measure_line(nanovg_context, &mut document, line, self.word_wrap, text_width);
+            let cursor_top = (document.rows_before(line) + document.row_of(document.cursor)) as f32 * row_height;
+            if cursor_top < self.scroll.get() {
+                self.scroll.set(cursor_top);
+            } else if cursor_top + row_height > self.scroll.get() + view_height {
+                self.scroll.set(cursor_top + row_height - view_height);
+            }
+        }
+
+        // find the first line in view, measuring lines as they scroll into view
+        let mut line = 0;
+        let mut line_top = 0.0;
+        while line < document.lines.len() {
+            let rows = document.row_starts(line).len() as f32;
+            if line_top + rows * row_height > self.scroll.get() {
+                break;
+            }
+            line_top += rows * row_height;
+            line += 1;
+        }
+
+        nanovg_context.save();
+        nanovg_context.intersect_scissor(0.0, PADDING, w, view_height);
+
+        let text_color = if enabled { theme.text_color() } else { theme.disabled_text_color() };
+        let selection = document.selection();
+        let composition = self.composition.borrow();
+        let mut drawn_rows = Vec::new();
+        let mut row_y = PADDING + line_top - self.scroll.get();
+
+        while line < document.lines.len() && row_y < h {
+            measure_line(nanovg_context, &mut document, line, self.word_wrap, text_width);
+
+            if self.line_numbers {
+                nanovg_context.text_align(nanovg::RIGHT | nanovg::TOP);
+                nanovg_context.fill_color(theme.disabled_text_color().to_nanovg_color());
+                nanovg_context.text(gutter - PADDING, row_y, &format!("{}", line + 1));
+                nanovg_context.text_align(nanovg::LEFT | nanovg::TOP);
+            }
+
+            let row_count = document.row_starts(line).len();
+            for row in 0..row_count {
+                let (start, end) = document.row_range(line, row);
+                let row_text: String = document.lines[line].chars().skip(start).take(end - start).collect();
+                let carets = caret_offsets(nanovg_context, &row_text);
+                let measure = |col: usize| -> f32 { carets[col - start] };
+
+                if let Some((sel_start, sel_end)) = selection {
+                    if sel_start <= (line, end) && sel_end >= (line, start) {
+                        let from = if sel_start.0 == line { sel_start.1.max(start).min(end) } else { start };
+                        let to = if sel_end.0 == line { sel_end.1.max(start).min(end) } else { end };
+                        // selected line breaks show as a small block
+                        let extra = if sel_end.0 > line && row + 1 == row_count { font_size / 3.0 } else { 0.0 };
+                        let from_x = measure(from);
+                        let to_x = measure(to) + extra;
+
+                        nanovg_context.begin_path();
+                        nanovg_context.rect(text_x + from_x, row_y, to_x - from_x, row_height);
+                        nanovg_context.fill_color(theme.text_selection_color().to_nanovg_color());
+                        nanovg_context.fill();
+                    }
+                }
+
+                let caret_row = editing && document.cursor.0 == line && document.row_of(document.cursor) == row;
+                if caret_row && composition.0.len() > 0 {
+                    // the composition of an input method is shown inline at the caret
+                    let split = document.cursor.1 - start;
+                    let length = composition.0.chars().count();
+                    let mut shown: String = row_text.chars().take(split).collect();
+                    shown.push_str(&composition.0);
+                    shown.extend(row_text.chars().skip(split));
+                    let shown_carets = caret_offsets(nanovg_context, &shown);
+                    let composed = |offset: i32| shown_carets[split + (offset.max(0) as usize).min(length)];
+
+                    if composition.2 > 0 {
+                        let (begin, end) = (composed(composition.1), composed(composition.1 + composition.2));
+                        nanovg_context.begin_path();
+                        nanovg_context.rect(text_x + begin, row_y, end - begin, row_height);
+                        nanovg_context.fill_color(theme.text_selection_color().to_nanovg_color());
+                        nanovg_context.fill();
+                    }
+
+                    nanovg_context.fill_color(text_color.to_nanovg_color());
+                    nanovg_context.text(text_x, row_y, &shown);
+
+                    nanovg_context.begin_path();
+                    nanovg_context.move_to(text_x + composed(0), row_y + row_height - 1.0);
+                    nanovg_context.line_to(text_x + composed(length as i32), row_y + row_height - 1.0);
+                    nanovg_context.stroke_color(text_color.to_nanovg_color());
+                    nanovg_context.stroke_width(1.0);
+                    nanovg_context.stroke();
+
+                    let caret_x = text_x + composed(composition.1);
+                    draw_caret(nanovg_context, caret_x, row_y, row_height);
+                    self.caret_rect.set(Some((caret_x, row_y, 1.0, row_height)));
+                } else {
+                    nanovg_context.fill_color(text_color.to_nanovg_color());
+                    nanovg_context.text(text_x, row_y, &row_text);
+
+                    if caret_row {
+                        let caret_x = text_x + measure(document.cursor.1);
+                        draw_caret(nanovg_context, caret_x, row_y, row_height);
+                        self.caret_rect.set(Some((caret_x, row_y, 1.0, row_height)));
+                    }
+                }
+
+                drawn_rows.push(DrawnRow {
+                    line: line,
+                    row: row,
+                    start: start,
+                    top: row_y,
+                    carets: carets.iter().map(|x| text_x + x).collect()
+                });
+                row_y += row_height;
+            }
+
+            line += 1;
+        }
+
+        nanovg_context.restore();
+        *self.drawn_rows.borrow_mut() = drawn_rows;
+
+        let total_height = document.row_count() as f32 * row_height;
+        if total_height > view_height {
+            let scroll = self.scroll.get() / (total_height - view_height);
+            let thumb_height = (view_height * view_height / total_height).max(16.0).min(h - 8.0);
+
+            let track = nanovg_context.box_gradient(w - 12.0 + 1.0, 4.0 + 1.0, 8.0, h - 8.0, 3.0, 4.0,
+                nanovg::Color::rgba(0, 0, 0, 32), nanovg::Color::rgba(0, 0, 0, 92));
+            nanovg_context.begin_path();
+            nanovg_context.rounded_rect(w - 12.0, 4.0, 8.0, h - 8.0, 3.0);
+            nanovg_context.fill_paint(track);
+            nanovg_context.fill();
+
+            let thumb_y = 4.0 + (h - 8.0 - thumb_height) * scroll;
+            let thumb = nanovg_context.box_gradient(w - 12.0 - 1.0, thumb_y - 1.0, 8.0, thumb_height, 3.0, 4.0,
+                nanovg::Color::rgba(220, 220, 220, 100), nanovg::Color::rgba(128, 128, 128, 100));
+            nanovg_context.begin_path();
+            nanovg_context.rounded_rect(w - 12.0 + 1.0, thumb_y + 1.0, 8.0 - 2.0, thumb_height - 2.0, 2.0);
+            nanovg_context.fill_paint(thumb);
+            nanovg_context.fill();
+        }
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.editable
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.caret_rect.get()
+    }
+
+    // events
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        if button != Mouse::Left {
+            return self.widget.mouse_button_event(p, button, down, mods)
+        }
+
+        if down {
+            if !self.widget.focused {
+                self.request_focus();
+            }
+
+            let local = (p.0 as f32 - self.widget.pos.0 as f32, p.1 as f32 - self.widget.pos.1 as f32);
+            if let Some(pos) = self.position_at(local) {
+                self.document.borrow_mut().set_cursor(pos, mods as u32 & KMOD_SHIFT != 0);
+            }
+            self.mouse_selecting.set(true);
+        } else {
+            self.mouse_selecting.set(false);
+        }
+
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), _: (i32, i32), _: Mouse, _: SDL_Keymod) -> bool {
+        if !self.mouse_selecting.get() {
+            return false
+        }
+
+        let local = (p.0 as f32 - self.widget.pos.0 as f32, p.1 as f32 - self.widget.pos.1 as f32);
+        if let Some(pos) = self.position_at(local) {
+            self.document.borrow_mut().set_cursor(pos, true);
+        }
+        self.scroll_to_cursor.set(true);
+        true
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, _: (u32, u32), rel: (i32, i32)) -> bool {
+        if rel.1 == 0 || self.max_scroll() == 0.0 {
+            return false
+        }
+
+        let scroll = self.scroll.get() - (rel.1 * SCROLL_ROWS) as f32 * self.row_height();
+        self.scroll.set(scroll);
+        self.clamp_scroll();
+        true
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused);
+
+        if !focused {
+            *self.composition.borrow_mut() = (String::new(), 0, 0);
+        }
+
+        if !focused && self.modified.get() {
+            self.modified.set(false);
+            if let Some(ref val) = self.on_change {
+                val(&self.document.borrow().text());
+            }
+        }
+
+        false
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        if !self.widget.focused || !pressed {
+            return self.widget.keyboard_event(key, scancode, pressed, mods)
+        }
+
+        let scancode = match scancode {
+            Some(val) => val,
+            None => return false
+        };
+
+        let mods = mods as u32;
+        let shift = mods & KMOD_SHIFT != 0;
+        let command = mods & (KMOD_CTRL | KMOD_GUI) != 0;
+        let word = mods & (KMOD_CTRL | KMOD_ALT) != 0;
+        let editable = self.editable;
+        let page_rows = ((self.widget.size.1 as f32 - 2.0 * PADDING) / self.row_height()).max(1.0) as i32;
+        let mut document = self.document.borrow_mut();
+
+        let edited = match scancode {
+            Scancode::Left => {
+                let pos = document.position_left(document.cursor, word);
+                document.set_cursor(pos, shift);
+                false
+            },
+            Scancode::Right => {
+                let pos = document.position_right(document.cursor, word);
+                document.set_cursor(pos, shift);
+                false
+            },
+            Scancode::Up => {
+                let pos = document.position_vertical(document.cursor, -1);
+                document.set_cursor(pos, shift);
+                false
+            },
+            Scancode::Down => {
+                let pos = document.position_vertical(document.cursor, 1);
+                document.set_cursor(pos, shift);
+                false
+            },
+            Scancode::PageUp => {
+                let pos = document.position_vertical(document.cursor, -page_rows);
+                document.set_cursor(pos, shift);
+                false
+            },
+            Scancode::PageDown => {
+                let pos = document.position_vertical(document.cursor, page_rows);
+                document.set_cursor(pos, shift);
+                false
+            },
+            Scancode::Home => {
+                let pos = if command {
+                    (0, 0)
+                } else {
+                    let row = document.row_of(document.cursor);
+                    (document.cursor.0, document.row_range(document.cursor.0, row).0)
+                };
+                document.set_cursor(pos, shift);
+                false
+            },
+            Scancode::End => {
+                let pos = if command {
+                    document.end()
+                } else {
+                    let row = document.row_of(document.cursor);
+                    (document.cursor.0, document.row_column(document.cursor.0, row, usize::max_value()))
+                };
+                document.set_cursor(pos, shift);
+                false
+            },
+            Scancode::Return | Scancode::KpEnter if editable => {
+                document.insert("\n");
+                true
+            },
+            Scancode::Backspace if editable => document.delete_backward(word),
+            Scancode::Delete if editable => document.delete_forward(word),
+            Scancode::A if command => {
+                let end = document.end();
+                document.set_cursor((0, 0), false);
+                document.set_cursor(end, true);
+                false
+            },
+            Scancode::C if command => {
+                if let Some((start, end)) = document.selection() {
+                    set_clipboard_text(self, &document.range_text(start, end));
+                }
+                false
+            },
+            Scancode::X if command && editable => {
+                match document.selection() {
+                    Some((start, end)) => {
+                        set_clipboard_text(self, &document.range_text(start, end));
+                        document.delete_backward(false)
+                    },
+                    None => false
+                }
+            },
+            Scancode::V if command && editable => {
+                match clipboard_text(self) {
+                    Some(val) => {
+                        document.insert(&split_lines(&val).join("\n"));
+                        true
+                    },
+                    None => false
+                }
+            },
+            Scancode::Z if command && editable => {
+                if shift { document.redo() } else { document.undo() }
+            },
+            Scancode::Y if command && editable => document.redo(),
+            // text arrives as text input events; other keys are left to the
+            // parents and to shortcuts
+            _ => return false
+        };
+
+        if edited {
+            self.modified.set(true);
+        }
+        self.scroll_to_cursor.set(true);
+        true
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        if !self.editable || !self.widget.focused {
+            return false
+        }
+
+        *self.composition.borrow_mut() = (String::new(), 0, 0);
+        self.document.borrow_mut().insert(text);
+        self.modified.set(true);
+        self.scroll_to_cursor.set(true);
+        true
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        if !self.editable || !self.widget.focused {
+            return false
+        }
+
+        *self.composition.borrow_mut() = (text.to_string(), start, length);
+        self.scroll_to_cursor.set(true);
+        true
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl TextArea {
+    pub fn new(id: String, text: String) -> Rc<RefCell<TextArea>> {
+        let mut widget = WidgetObj::new(id);
+        widget.cursor = Cursor::IBeam;
+
+        Rc::new(RefCell::new(TextArea {
+            widget: widget,
+            document: RefCell::new(Document::new(&text)),
+            editable: true,
+            word_wrap: true,
+            line_numbers: false,
+            modified: Cell::new(false),
+            scroll: Cell::new(0.0),
+            scroll_to_cursor: Cell::new(false),
+            wrap_key: Cell::new((0.0, 0)),
+            drawn_rows: RefCell::new(Vec::new()),
+            composition: RefCell::new((String::new(), 0, 0)),
+            caret_rect: Cell::new(None),
+            mouse_selecting: Cell::new(false),
+            on_change: None
+        }))
+    }
+
+    pub fn text(&self) -> String {
+        self.document.borrow().text()
+    }
+
+    /// Replaces the whole text, which also clears the undo history.
+    pub fn set_text(&mut self, text: String) {
+        *self.document.borrow_mut() = Document::new(&text);
+        self.scroll.set(0.0);
+    }
+
+    /// Adds text at the end without touching the undo history, e.g. for logs. A
+    /// view that was scrolled to the bottom follows the new text.
+    pub fn append_text(&mut self, text: &str) {
+        let at_bottom = self.scroll.get() >= self.max_scroll() - 1.0;
+
+        let mut document = self.document.borrow_mut();
+        let end = document.end();
+        document.replace(end, end, text);
+        drop(document);
+
+        if at_bottom {
+            self.scroll.set(self.max_scroll());
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.document.borrow().lines.len()
+    }
+
+    pub fn line(&self, index: usize) -> Option<String> {
+        self.document.borrow().lines.get(index).cloned()
+    }
+
+    pub fn cursor_pos(&self) -> TextPos {
+        self.document.borrow().cursor
+    }
+
+    pub fn set_cursor_pos(&mut self, pos: TextPos) {
+        self.document.borrow_mut().set_cursor(pos, false);
+        self.scroll_to_cursor.set(true);
+    }
+
+    pub fn selection(&self) -> Option<(TextPos, TextPos)> {
+        self.document.borrow().selection()
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let document = self.document.borrow();
+        document.selection().map(|(start, end)| document.range_text(start, end))
+    }
+
+    /// The pre-edit text an input method is composing, shown at the caret until it
+    /// is committed as text input.
+    pub fn composition(&self) -> String {
+        self.composition.borrow().0.clone()
+    }
+
+    impl_get_set!(editable, bool);
+    impl_get_set!(line_numbers, bool);
+
+    pub fn word_wrap(&self) -> bool {
+        self.word_wrap
+    }
+
+    pub fn set_word_wrap(&mut self, word_wrap: bool) {
+        self.word_wrap = word_wrap;
+        self.scroll_to_cursor.set(true);
+    }
+
+    pub fn scroll(&self) -> f32 {
+        self.scroll.get()
+    }
+
+    /// Called with the whole text when the area loses focus after being edited.
+    pub fn set_on_change(&mut self, callback: Option<Box<Fn(&str)>>) {
+        self.on_change = callback;
+    }
+
+    fn row_height(&self) -> f32 {
+        (self.font_size() as f32 * LINE_HEIGHT).ceil()
+    }
+
+    fn max_scroll(&self) -> f32 {
+        let total_height = self.document.borrow().row_count() as f32 * self.row_height();
+        (total_height - (self.widget.size.1 as f32 - 2.0 * PADDING)).max(0.0)
+    }
+
+    fn clamp_scroll(&self) {
+        let scroll = self.scroll.get().min(self.max_scroll()).max(0.0);
+        self.scroll.set(scroll);
+    }
+
+    // The text position at `local`, in widget coordinates. Points above the first
+    // drawn row resolve to that row, so dragging upwards selects towards the start.
+    // Points below the last row go to the end of the text, or to the last drawn row
+    // when the text continues below the view. None before the first draw.
+    fn position_at(&self, local: (f32, f32)) -> Option<TextPos> {
+        let drawn_rows = self.drawn_rows.borrow();
+        let document = self.document.borrow();
+        let (first, last) = match (drawn_rows.first(), drawn_rows.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return None
+        };
+
+        let last_row = last.line + 1 == document.lines.len() && last.row + 1 == document.row_starts(last.line).len();
+        if last_row && local.1 >= last.top + self.row_height() {
+            return Some(document.end())
+        }
+
+        let drawn = drawn_rows.iter().rev().find(|x| x.top <= local.1).unwrap_or(first);
+        let offset = nearest_caret(&drawn.carets, local.0);
+        // the rows are from the last draw, the text may have changed since
+        let col = if drawn.line < document.lines.len() && drawn.row < document.row_starts(drawn.line).len() {
+            document.row_column(drawn.line, drawn.row, offset)
+        } else {
+            drawn.start + offset
+        };
+        Some(document.clamp((drawn.line, col)))
+    }
+}
+
+// Wraps a line into rows no wider than `width`, breaking after whitespace where
+// possible. Lines keep their rows until they are edited or the width changes.
+fn measure_line(nanovg_context: &nanovg::Context, document: &mut Document, line: usize, word_wrap: bool, width: f32) {
+    if document.rows[line].is_some() {
+        return
+    }
+
+    if !word_wrap {
+        document.rows[line] = Some(vec![0]);
+        return
+    }
+
+    let chars: Vec<char> = document.lines[line].chars().collect();
+    let mut starts = vec![0];
+    let mut start = 0;
+
+    while start < chars.len() {
+        // the most chars from `start` that fit, at least one
+        let (mut low, mut high) = (1, chars.len() - start);
+        while low < high {
+            let mid = (low + high + 1) / 2;
+            let text: String = chars[start..start + mid].iter().cloned().collect();
+            if nanovg_context.text_bounds(0.0, 0.0, &text, None) <= width {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        if start + low >= chars.len() {
+            break;
+        }
+
+        let mut next = start + low;
+        if let Some(val) = (start + 1..next + 1).rev().find(|x| chars[x - 1].is_whitespace()) {
+            next = val;
+        }
+
+        starts.push(next);
+        start = next;
+    }
+
+    document.rows[line] = Some(starts);
+}
+
+// The x of every caret position in `text`, relative to where the text starts.
+fn caret_offsets(nanovg_context: &nanovg::Context, text: &str) -> Vec<f32> {
+    let mut offsets = vec![0.0];
+    let mut prefix = String::new();
+    for c in text.chars() {
+        prefix.push(c);
+        offsets.push(nanovg_context.text_bounds(0.0, 0.0, &prefix, None));
+    }
+    offsets
+}
+
+fn draw_caret(nanovg_context: &nanovg::Context, x: f32, top: f32, height: f32) {
+    nanovg_context.begin_path();
+    nanovg_context.move_to(x, top);
+    nanovg_context.line_to(x, top + height);
+    nanovg_context.stroke_color(nanovg::Color::rgba(255, 192, 0, 255));
+    nanovg_context.stroke_width(1.0);
+    nanovg_context.stroke();
+}
+
+// The index of the caret position closest to `x`.
+fn nearest_caret(carets: &[f32], x: f32) -> usize {
+    let mut best = 0;
+    for (index, val) in carets.iter().enumerate() {
+        if (val - x).abs() < (carets[best] - x).abs() {
+            best = index;
+        }
+    }
+    best
+}
@@ -0,0 +1,753 @@
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use self::sdl2::keyboard::{Mod, Scancode};
+use self::sdl2::mouse::Mouse;
+use self::sdl2_sys::keycode::SDL_Keymod;
+use common::Cursor;
+use shortcut::{KMOD_ALT, KMOD_CTRL, KMOD_GUI, KMOD_SHIFT};
+use text_buffer::{TextBuffer, byte_index};
+use widget::{Widget, WidgetObj};
+use widget_container::{clipboard_text, set_clipboard_text};
+use theme::Theme;
+use layout::Layout;
+use window::Window;
+use screen::Screen;
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum TextAlignment {
+    Left,
+    Center,
+    Right
+}
+
+// The committed value only changes when editing is finished, by pressing Enter or
+// by moving the focus away; until then the edits live in the buffer. Events only
+// get &self, so all editing state sits behind Cell/RefCell.
+pub struct TextBox {
+    widget: WidgetObj,
+    value: RefCell<String>,
+    buffer: RefCell<TextBuffer>,
+    composition: RefCell<(String, i32, i32)>,
+    placeholder: String,
+    units: String,
+    alignment: TextAlignment,
+    left_padding: u32,
+    editable: bool,
+    valid: Cell<bool>,
+    mouse_selecting: Cell<bool>,
+    text_offset: Cell<f32>,
+    // caret x positions of the last drawn text, relative to the widget
+    caret_positions: RefCell<Vec<f32>>,
+    // the caret as last drawn, for the candidate window of input methods
+    caret_rect: Cell<Option<(f32, f32, f32, f32)>>,
+    format: Option<Box<Fn(&str) -> bool>>,
+    on_change: Option<Box<Fn(&str)>>
+}
+
+impl Widget for TextBox {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent.as_ref()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.parent = match parent {
+            Some(val) => { Some(Rc::downgrade(&val)) },
+            None => None
+        }
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children.clone()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        &mut self.widget.children
+    }
+
+    // get/set
+
+    fn id(&self) -> String {
+        self.widget.id.clone()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.id = id;
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.pos = p;
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.size = s;
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.fixed_size = s;
+    }
+
+    fn font_size(&self) -> u32 {
+        match self.widget.font_size {
+            Some(val) => val,
+            None => match self.widget.theme {
+                Some(ref theme) => theme.borrow().textbox_font_size(),
+                None => 20
+            }
+        }
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.font_size = s;
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme.as_ref()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.theme = theme;
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.enabled = enabled;
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip.clone()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.tooltip = tooltip;
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.visible = visible;
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.focused = focused;
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout.as_ref()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.layout = layout;
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.cursor = cursor;
+    }
+
+    // misc
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context);
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        let height = (self.font_size() as f32 * 1.4) as u32;
+
+        nanovg_context.font_face(&self.widget.font());
+        nanovg_context.font_size(self.font_size() as f32);
+        nanovg_context.text_align(nanovg::LEFT | nanovg::MIDDLE);
+
+        let units_width = if self.units.len() > 0 {
+            nanovg_context.text_bounds(0.0, 0.0, &self.units, None) + 2.0
+        } else {
+            0.0
+        } + self.left_padding as f32;
+
+        let value = self.value.borrow();
+        let text = if value.len() > 0 { &*value } else { &self.placeholder };
+        let text_width = nanovg_context.text_bounds(0.0, 0.0, text, None);
+
+        let fixed = self.widget.fixed_size;
+        (if fixed.0 > 0 { fixed.0 } else { height + (text_width + units_width) as u32 },
+         if fixed.1 > 0 { fixed.1 } else { height })
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        self.widget.draw(nanovg_context);
+        self.caret_rect.set(None);
+
+        let theme_rc = match self.widget.theme {
+            Some(ref val) => val.clone(),
+            None => return
+        };
+        let theme = theme_rc.borrow();
+
+        let (w, h) = (self.widget.size.0 as f32, self.widget.size.1 as f32);
+        let editing = self.editable && self.widget.focused;
+        let enabled = self.widget.enabled_recursive();
+
+        let background = if editing && !self.valid.get() {
+            nanovg_context.box_gradient(1.0, 2.0, w - 2.0, h - 2.0, 3.0, 4.0,
+                nanovg::Color::rgba(255, 0, 0, 100), nanovg::Color::rgba(255, 0, 0, 50))
+        } else if editing {
+            nanovg_context.box_gradient(1.0, 2.0, w - 2.0, h - 2.0, 3.0, 4.0,
+                nanovg::Color::rgba(150, 150, 150, 32), nanovg::Color::rgba(32, 32, 32, 32))
+        } else {
+            nanovg_context.box_gradient(1.0, 2.0, w - 2.0, h - 2.0, 3.0, 4.0,
+                nanovg::Color::rgba(255, 255, 255, 32), nanovg::Color::rgba(32, 32, 32, 32))
+        };
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(1.0, 2.0, w - 2.0, h - 2.0, 3.0);
+        nanovg_context.fill_paint(background);
+        nanovg_context.fill();
+
+        nanovg_context.begin_path();
+        nanovg_context.rounded_rect(0.5, 0.5, w - 1.0, h - 1.0, 2.5);
+        nanovg_context.stroke_color(nanovg::Color::rgba(0, 0, 0, 48));
+        nanovg_context.stroke();
+
+        nanovg_context.font_face(&self.widget.font());
+        nanovg_context.font_size(self.font_size() as f32);
+
+        let text_left = 5.0 + self.left_padding as f32;
+        let mut text_right = w - 5.0;
+        let center_y = h / 2.0;
+
+        if self.units.len() > 0 {
+            nanovg_context.text_align(nanovg::RIGHT | nanovg::MIDDLE);
+            nanovg_context.fill_color(nanovg::Color::rgba(255, 255, 255, if enabled { 64 } else { 32 }));
+            nanovg_context.text(text_right, center_y, &self.units);
+            text_right -= nanovg_context.text_bounds(0.0, 0.0, &self.units, None) + 2.0;
+        }
+
+        // the composition of an input method is shown inline at the caret
+        let buffer = self.buffer.borrow();
+        let composition = self.composition.borrow();
+        let (text, caret) = if editing {
+            let split = byte_index(buffer.text(), buffer.cursor());
+            let mut text = buffer.text().to_string();
+            text.insert_str(split, &composition.0);
+            (text, buffer.cursor() + composition.1.max(0) as usize)
+        } else {
+            (self.value.borrow().clone(), 0)
+        };
+
+        nanovg_context.text_align(nanovg::LEFT | nanovg::MIDDLE);
+
+        if text.len() == 0 && !editing {
+            nanovg_context.fill_color(theme.disabled_text_color().to_nanovg_color());
+            nanovg_context.text(text_left, center_y, &self.placeholder);
+            self.caret_positions.borrow_mut().clear();
+            return
+        }
+
+        let mut positions: Vec<f32> = Vec::new();
+        let mut prefix = String::new();
+        positions.push(0.0);
+        for c in text.chars() {
+            prefix.push(c);
+            positions.push(nanovg_context.text_bounds(0.0, 0.0, &prefix, None));
+        }
+
+        let text_width = *positions.last().unwrap();
+        let available = text_right - text_left;
+        let start = if text_width > available {
+            // scroll so the caret stays visible
+            let caret_x = positions[caret.min(positions.len() - 1)];
+            let mut offset = self.text_offset.get();
+            if caret_x - offset > available {
+                offset = caret_x - available;
+            }
+            if caret_x - offset < 0.0 {
+                offset = caret_x;
+            }
+            offset = offset.min(text_width - available).max(0.0);
+            self.text_offset.set(offset);
+            text_left - offset
+        } else {
+            self.text_offset.set(0.0);
+            match self.alignment {
+                TextAlignment::Left => text_left,
+                TextAlignment::Center => text_left + (available - text_width) / 2.0,
+                TextAlignment::Right => text_right - text_width
+            }
+        };
+
+        nanovg_context.save();
+        nanovg_context.intersect_scissor(text_left - 5.0, 0.0, text_right - text_left + 5.0, h);
+
+        let font_size = self.font_size() as f32;
+        if editing {
+            let mut highlights: Vec<(usize, usize)> = Vec::new();
+            if let Some(val) = buffer.selection() {
+                highlights.push(val);
+            }
+            if composition.2 > 0 {
+                let begin = buffer.cursor() + composition.1.max(0) as usize;
+                highlights.push((begin, begin + composition.2 as usize));
+            }
+
+            for &(begin, end) in &highlights {
+                let begin = positions[begin.min(positions.len() - 1)];
+                let end = positions[end.min(positions.len() - 1)];
+                nanovg_context.begin_path();
+                nanovg_context.rect(start + begin, center_y - font_size / 2.0, end - begin, font_size);
+                nanovg_context.fill_color(theme.text_selection_color().to_nanovg_color());
+                nanovg_context.fill();
+            }
+        }
+
+        let text_color = if enabled { theme.text_color() } else { theme.disabled_text_color() };
+        nanovg_context.fill_color(text_color.to_nanovg_color());
+        nanovg_context.text(start, center_y, &text);
+
+        if editing {
+            if composition.0.len() > 0 {
+                let begin = positions[buffer.cursor()];
+                let end = positions[buffer.cursor() + composition.0.chars().count()];
+                nanovg_context.begin_path();
+                nanovg_context.move_to(start + begin, center_y + font_size / 2.0);
+                nanovg_context.line_to(start + end, center_y + font_size / 2.0);
+                nanovg_context.stroke_color(text_color.to_nanovg_color());
+                nanovg_context.stroke_width(1.0);
+                nanovg_context.stroke();
+            }
+
+            let caret_x = start + positions[caret.min(positions.len() - 1)];
+            self.caret_rect.set(Some((caret_x, center_y - font_size / 2.0, 1.0, font_size)));
+            nanovg_context.begin_path();
+            nanovg_context.move_to(caret_x, center_y - font_size / 2.0);
+            nanovg_context.line_to(caret_x, center_y + font_size / 2.0);
+            nanovg_context.stroke_color(nanovg::Color::rgba(255, 192, 0, 255));
+            nanovg_context.stroke_width(1.0);
+            nanovg_context.stroke();
+        }
+
+        nanovg_context.restore();
+
+        // mouse positions are mapped to chars with the layout that was drawn last
+        if composition.0.len() == 0 {
+            *self.caret_positions.borrow_mut() = positions.iter().map(|val| start + val).collect();
+        }
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                let (par_x, par_y) = val_upgraded.borrow().absolute_position();
+                return (par_x + self.widget.pos.0, par_y + self.widget.pos.1)
+            }
+        }
+
+        return self.widget.pos.clone();
+    }
+
+    fn visible_recursive(&self) -> bool {
+        if !self.widget.visible {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().visible_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus();
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.editable
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.caret_rect.get()
+    }
+
+    // events
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        if button != Mouse::Left {
+            return self.widget.mouse_button_event(p, button, down, mods)
+        }
+
+        if down {
+            if !self.widget.focused {
+                self.buffer.borrow_mut().set_text(self.value.borrow().clone());
+                self.request_focus();
+            }
+
+            let pos = self.position_at(p.0);
+            self.buffer.borrow_mut().set_cursor(pos, mods as u32 & KMOD_SHIFT != 0);
+            self.mouse_selecting.set(true);
+        } else {
+            self.mouse_selecting.set(false);
+        }
+
+        true
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), _: (i32, i32), _: Mouse, _: SDL_Keymod) -> bool {
+        if !self.mouse_selecting.get() {
+            return false
+        }
+
+        let pos = self.position_at(p.0);
+        self.buffer.borrow_mut().set_cursor(pos, true);
+        true
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        self.widget.scroll_event(p, rel)
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused);
+
+        if focused {
+            // a click already placed the caret
+            if !self.mouse_selecting.get() {
+                self.buffer.borrow_mut().set_text(self.value.borrow().clone());
+            }
+        } else {
+            *self.composition.borrow_mut() = (String::new(), 0, 0);
+            self.commit();
+            self.mouse_selecting.set(false);
+        }
+
+        false
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        if !self.widget.focused || !pressed {
+            return self.widget.keyboard_event(key, scancode, pressed, mods)
+        }
+
+        let scancode = match scancode {
+            Some(val) => val,
+            None => return false
+        };
+
+        let mods = mods as u32;
+        let shift = mods & KMOD_SHIFT != 0;
+        let command = mods & (KMOD_CTRL | KMOD_GUI) != 0;
+        let word = mods & (KMOD_CTRL | KMOD_ALT) != 0;
+        let editable = self.editable;
+
+        let edited = match scancode {
+            Scancode::Left => {
+                self.buffer.borrow_mut().move_left(shift, word);
+                false
+            },
+            Scancode::Right => {
+                self.buffer.borrow_mut().move_right(shift, word);
+                false
+            },
+            Scancode::Home => {
+                self.buffer.borrow_mut().set_cursor(0, shift);
+                false
+            },
+            Scancode::End => {
+                let len = self.buffer.borrow().len();
+                self.buffer.borrow_mut().set_cursor(len, shift);
+                false
+            },
+            Scancode::Backspace if editable => self.buffer.borrow_mut().delete_backward(word),
+            Scancode::Delete if editable => self.buffer.borrow_mut().delete_forward(word),
+            Scancode::Return | Scancode::KpEnter if editable => {
+                self.commit();
+                false
+            },
+            Scancode::Escape if editable => {
+                self.buffer.borrow_mut().set_text(self.value.borrow().clone());
+                true
+            },
+            Scancode::A if command => {
+                self.buffer.borrow_mut().select_all();
+                false
+            },
+            Scancode::C if command => {
+                let selected = self.buffer.borrow().selected_text();
+                if let Some(val) = selected {
+                    set_clipboard_text(self, &val);
+                }
+                false
+            },
+            Scancode::X if command && editable => {
+                let selected = self.buffer.borrow().selected_text();
+                match selected {
+                    Some(val) => {
+                        set_clipboard_text(self, &val);
+                        self.buffer.borrow_mut().delete_backward(false)
+                    },
+                    None => false
+                }
+            },
+            Scancode::V if command && editable => {
+                match clipboard_text(self) {
+                    Some(val) => {
+                        // a single-line box keeps only the first line of the pasted text
+                        let line = val.lines().next().unwrap_or("").to_string();
+                        self.buffer.borrow_mut().insert(&line);
+                        true
+                    },
+                    None => false
+                }
+            },
+            Scancode::Z if command && editable => {
+                if shift {
+                    self.buffer.borrow_mut().redo()
+                } else {
+                    self.buffer.borrow_mut().undo()
+                }
+            },
+            Scancode::Y if command && editable => self.buffer.borrow_mut().redo(),
+            // text arrives as text input events; other keys are left to the
+            // parents and to shortcuts
+            _ => return false
+        };
+
+        if edited {
+            self.validate();
+        }
+
+        true
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        if !self.editable || !self.widget.focused {
+            return false
+        }
+
+        *self.composition.borrow_mut() = (String::new(), 0, 0);
+        self.buffer.borrow_mut().insert(text);
+        self.validate();
+        true
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        if !self.editable || !self.widget.focused {
+            return false
+        }
+
+        *self.composition.borrow_mut() = (text.to_string(), start, length);
+        true
+    }
+
+    // casts
+
+    fn as_window(&self) -> Option<&Window> {
+        None
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        None
+    }
+}
+
+impl TextBox {
+    pub fn new(id: String, value: String) -> Rc<RefCell<TextBox>> {
+        Rc::new(RefCell::new(TextBox::create(id, value)))
+    }
+
+    /// Creates the text box without wrapping it, for widgets built on top of it.
+    pub fn create(id: String, value: String) -> TextBox {
+        let mut widget = WidgetObj::new(id);
+        widget.cursor = Cursor::IBeam;
+
+        TextBox {
+            widget: widget,
+            buffer: RefCell::new(TextBuffer::new(value.clone())),
+            value: RefCell::new(value),
+            composition: RefCell::new((String::new(), 0, 0)),
+            placeholder: String::new(),
+            units: String::new(),
+            alignment: TextAlignment::Left,
+            left_padding: 0,
+            editable: true,
+            valid: Cell::new(true),
+            mouse_selecting: Cell::new(false),
+            text_offset: Cell::new(0.0),
+            caret_positions: RefCell::new(Vec::new()),
+            caret_rect: Cell::new(None),
+            format: None,
+            on_change: None
+        }
+    }
+
+    /// The committed value. Text that is still being edited is in editing_value.
+    pub fn value(&self) -> String {
+        self.value.borrow().clone()
+    }
+
+    pub fn set_value(&mut self, value: String) {
+        self.replace_value(value);
+    }
+
+    /// Like set_value, but usable from event handlers, which only get &self.
+    /// Discards any edit in progress and does not call on_change.
+    pub fn replace_value(&self, value: String) {
+        self.buffer.borrow_mut().set_text(value.clone());
+        *self.value.borrow_mut() = value;
+        self.validate();
+    }
+
+    pub fn editing_value(&self) -> String {
+        self.buffer.borrow().text().to_string()
+    }
+
+    pub fn cursor_pos(&self) -> usize {
+        self.buffer.borrow().cursor()
+    }
+
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        self.buffer.borrow().selection()
+    }
+
+    impl_get_set_clone!(placeholder, String);
+    impl_get_set_clone!(units, String);
+    impl_get_set!(alignment, TextAlignment);
+
+    // Space kept free left of the text, for widgets that draw controls there.
+    impl_get_set!(left_padding, u32);
+
+    pub fn editable(&self) -> bool {
+        self.editable
+    }
+
+    /// A read-only box can still be focused to select and copy its text.
+    pub fn set_editable(&mut self, editable: bool) {
+        self.editable = editable;
+    }
+
+    /// Whether the text being edited passes the format validator.
+    pub fn valid(&self) -> bool {
+        self.valid.get()
+    }
+
+    /// Sets a validator for the text. Invalid text is highlighted while editing
+    /// and is reverted instead of committed.
+    pub fn set_format(&mut self, format: Option<Box<Fn(&str) -> bool>>) {
+        self.format = format;
+        self.validate();
+    }
+
+    /// Called with the new value whenever an edit is committed and changed the value.
+    pub fn set_on_change(&mut self, callback: Option<Box<Fn(&str)>>) {
+        self.on_change = callback;
+    }
+
+    fn validate(&self) {
+        let valid = match self.format {
+            Some(ref val) => val(self.buffer.borrow().text()),
+            None => true
+        };
+        self.valid.set(valid);
+    }
+
+    fn commit(&self) {
+        let text = self.buffer.borrow().text().to_string();
+        if !self.valid.get() {
+            self.buffer.borrow_mut().set_text(self.value.borrow().clone());
+            self.validate();
+            return
+        }
+
+        if *self.value.borrow() == text {
+            return
+        }
+
+        *self.value.borrow_mut() = text.clone();
+        if let Some(ref val) = self.on_change {
+            val(&text);
+        }
+    }
+
+    // Maps an x position in parent coordinates to the nearest caret position.
+    fn position_at(&self, x: u32) -> usize {
+        let positions = self.caret_positions.borrow();
+        if positions.len() == 0 {
+            return self.buffer.borrow().len()
+        }
+
+        let local_x = x as f32 - self.widget.pos.0 as f32;
+        let mut best = 0;
+        for (index, val) in positions.iter().enumerate() {
+            if (val - local_x).abs() < (positions[best] - local_x).abs() {
+                best = index;
+            }
+        }
+        best
+    }
+}
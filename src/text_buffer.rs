@@ -0,0 +1,275 @@
+use std::mem;
+
+// Editing state of the single-line text widgets (TextBox and the NumberBox built on
+// it): the text, the caret, the selection and the undo history. Positions are char
+// indices, not byte offsets. TextArea keeps lines and (line, column) positions in
+// its own document, but moves by words with word_start and word_end and keeps its
+// edits in an UndoHistory as well, so all text widgets behave the same.
+pub struct TextBuffer {
+    text: String,
+    cursor: usize,
+    anchor: Option<usize>,
+    // the text and caret before each edit
+    history: UndoHistory<(String, usize)>
+}
+
+impl TextBuffer {
+    pub fn new(text: String) -> TextBuffer {
+        let cursor = text.chars().count();
+        TextBuffer {
+            text: text,
+            cursor: cursor,
+            anchor: None,
+            history: UndoHistory::new()
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text, moves the caret to the end and forgets the undo history.
+    pub fn set_text(&mut self, text: String) {
+        self.cursor = text.chars().count();
+        self.text = text;
+        self.anchor = None;
+        self.history.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the caret to `pos`. With `extend` the selection grows from where the
+    /// caret was, otherwise it is cleared.
+    pub fn set_cursor(&mut self, pos: usize, extend: bool) {
+        if extend {
+            if self.anchor.is_none() {
+                self.anchor = Some(self.cursor);
+            }
+        } else {
+            self.anchor = None;
+        }
+
+        self.cursor = if pos > self.len() { self.len() } else { pos };
+    }
+
+    /// The selected range as (start, end), or None when nothing is selected.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        match self.anchor {
+            Some(val) if val < self.cursor => Some((val, self.cursor)),
+            Some(val) if val > self.cursor => Some((self.cursor, val)),
+            _ => None
+        }
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        self.selection().map(|(start, end)| self.text.chars().skip(start).take(end - start).collect())
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.len();
+    }
+
+    pub fn move_left(&mut self, extend: bool, word: bool) {
+        let pos = if !extend && !word && self.selection().is_some() {
+            self.selection().unwrap().0
+        } else if word {
+            word_start(&self.text, self.cursor)
+        } else {
+            self.cursor.saturating_sub(1)
+        };
+        self.set_cursor(pos, extend);
+    }
+
+    pub fn move_right(&mut self, extend: bool, word: bool) {
+        let pos = if !extend && !word && self.selection().is_some() {
+            self.selection().unwrap().1
+        } else if word {
+            word_end(&self.text, self.cursor)
+        } else {
+            self.cursor + 1
+        };
+        self.set_cursor(pos, extend);
+    }
+
+    pub fn word_start(&self, pos: usize) -> usize {
+        word_start(&self.text, pos)
+    }
+
+    pub fn word_end(&self, pos: usize) -> usize {
+        word_end(&self.text, pos)
+    }
+
+    /// Replaces the selection with `text`, or inserts it at the caret.
+    pub fn insert(&mut self, text: &str) {
+        self.push_undo();
+        self.remove_selection();
+
+        let index = byte_index(&self.text, self.cursor);
+        self.text.insert_str(index, text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Removes the selection, or the char (or word) before the caret. Returns
+    /// false when there was nothing to remove.
+    pub fn delete_backward(&mut self, word: bool) -> bool {
+        if self.selection().is_none() {
+            if self.cursor == 0 {
+                return false
+            }
+            let start = if word { word_start(&self.text, self.cursor) } else { self.cursor - 1 };
+            self.anchor = Some(start);
+        }
+
+        self.push_undo();
+        self.remove_selection();
+        true
+    }
+
+    /// Removes the selection, or the char (or word) after the caret. Returns
+    /// false when there was nothing to remove.
+    pub fn delete_forward(&mut self, word: bool) -> bool {
+        if self.selection().is_none() {
+            if self.cursor >= self.len() {
+                return false
+            }
+            let end = if word { word_end(&self.text, self.cursor) } else { self.cursor + 1 };
+            self.anchor = Some(end);
+        }
+
+        self.push_undo();
+        self.remove_selection();
+        true
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo() {
+            Some(val) => {
+                let current = self.restore(val);
+                self.history.undone(current);
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo() {
+            Some(val) => {
+                let current = self.restore(val);
+                self.history.redone(current);
+                true
+            },
+            None => false
+        }
+    }
+
+    // Every edit is its own undo step.
+    fn push_undo(&mut self) {
+        self.history.push((self.text.clone(), self.cursor));
+    }
+
+    // Switches to a text and caret from the history and returns the ones replaced.
+    fn restore(&mut self, (text, cursor): (String, usize)) -> (String, usize) {
+        let current = (mem::replace(&mut self.text, text), self.cursor);
+        self.cursor = cursor;
+        self.anchor = None;
+        current
+    }
+
+    fn remove_selection(&mut self) {
+        if let Some((start, end)) = self.selection() {
+            let start_index = byte_index(&self.text, start);
+            let end_index = byte_index(&self.text, end);
+            self.text.drain(start_index..end_index);
+            self.cursor = start;
+        }
+        self.anchor = None;
+    }
+}
+
+/// The byte offset of the char at `pos` in `text`, or its length past the end.
+pub fn byte_index(text: &str, pos: usize) -> usize {
+    match text.char_indices().nth(pos) {
+        Some((index, _)) => index,
+        None => text.len()
+    }
+}
+
+/// The start of the word before char `pos` of `text`, skipping whitespace in
+/// between; where moving or deleting a word to the left stops.
+pub fn word_start(text: &str, pos: usize) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut pos = if pos > chars.len() { chars.len() } else { pos };
+    while pos > 0 && chars[pos - 1].is_whitespace() {
+        pos -= 1;
+    }
+    while pos > 0 && !chars[pos - 1].is_whitespace() {
+        pos -= 1;
+    }
+    pos
+}
+
+/// The end of the word after char `pos` of `text`, skipping whitespace in between;
+/// where moving or deleting a word to the right stops.
+pub fn word_end(text: &str, pos: usize) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut pos = pos;
+    while pos < chars.len() && chars[pos].is_whitespace() {
+        pos += 1;
+    }
+    while pos < chars.len() && !chars[pos].is_whitespace() {
+        pos += 1;
+    }
+    pos
+}
+
+// The undo and redo stacks of the text widgets. What an entry holds is up to the
+// widget; undo and redo hand it out, and the widget returns the entry that
+// reverses it with undone or redone.
+pub struct UndoHistory<T> {
+    undo_stack: Vec<T>,
+    redo_stack: Vec<T>
+}
+
+impl<T> UndoHistory<T> {
+    pub fn new() -> UndoHistory<T> {
+        UndoHistory {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new()
+        }
+    }
+
+    /// Records a new edit, which invalidates whatever could be redone.
+    pub fn push(&mut self, entry: T) {
+        self.undo_stack.push(entry);
+        self.redo_stack.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    pub fn undo(&mut self) -> Option<T> {
+        self.undo_stack.pop()
+    }
+
+    pub fn undone(&mut self, entry: T) {
+        self.redo_stack.push(entry);
+    }
+
+    pub fn redo(&mut self) -> Option<T> {
+        self.redo_stack.pop()
+    }
+
+    pub fn redone(&mut self, entry: T) {
+        self.undo_stack.push(entry);
+    }
+}
@@ -7,10 +7,13 @@ pub struct Theme {
     font_normal: Option<nanovg::Font>,
     font_bold: Option<nanovg::Font>,
     font_icons: Option<nanovg::Font>,
+    standard_font: String,
+    window_title_font: String,
 
     standard_font_size: u32,
     button_font_size: u32,
     textbox_font_size: u32,
+    window_title_font_size: u32,
     window_corner_radius: u32,
     window_header_height: u32,
     window_dropshadow_size: u32,
@@ -45,6 +48,16 @@ pub struct Theme {
 
     window_popup: Color,
     window_popup_transparent: Color,
+
+    tooltip_background: Color,
+    tooltip_text_color: Color,
+
+    text_selection_color: Color,
+
+    scrollbar_track_inner: Color,
+    scrollbar_track_outer: Color,
+    scrollbar_thumb_inner: Color,
+    scrollbar_thumb_outer: Color,
 }
 
 impl Theme {
@@ -54,10 +67,13 @@ impl Theme {
             font_normal: Some(nanovg_context.create_font_mem("sans", resources::SANS_FONT).unwrap()),
             font_bold: Some(nanovg_context.create_font_mem("sans-bold", resources::SANS_BOLD_FONT).unwrap()),
             font_icons: Some(nanovg_context.create_font_mem("icons", resources::SANS_ICONS_FONT).unwrap()),
+            standard_font: "sans".to_string(),
+            window_title_font: "sans-bold".to_string(),
 
             standard_font_size: 16,
             button_font_size: 20,
             textbox_font_size: 20,
+            window_title_font_size: 18,
             window_corner_radius: 2,
             window_header_height: 30,
             window_dropshadow_size: 10,
@@ -92,6 +108,16 @@ impl Theme {
 
             window_popup: Color::from_intensity(50f32, 255f32),
             window_popup_transparent: Color::from_intensity(50f32, 0f32),
+
+            tooltip_background: Color::from_intensity(0f32, 255f32),
+            tooltip_text_color: Color::from_intensity(255f32, 255f32),
+
+            text_selection_color: Color::from_intensity(255f32, 80f32),
+
+            scrollbar_track_inner: Color::from_intensity(0f32, 32f32),
+            scrollbar_track_outer: Color::from_intensity(0f32, 92f32),
+            scrollbar_thumb_inner: Color::from_intensity(220f32, 100f32),
+            scrollbar_thumb_outer: Color::from_intensity(128f32, 100f32),
         }
     }
 
@@ -101,10 +127,13 @@ impl Theme {
             font_normal: None,
             font_bold: None,
             font_icons: None,
+            standard_font: "sans".to_string(),
+            window_title_font: "sans-bold".to_string(),
 
             standard_font_size: 16,
             button_font_size: 20,
             textbox_font_size: 20,
+            window_title_font_size: 18,
             window_corner_radius: 2,
             window_header_height: 30,
             window_dropshadow_size: 10,
@@ -139,6 +168,16 @@ impl Theme {
 
             window_popup: Color::from_intensity(50f32, 255f32),
             window_popup_transparent: Color::from_intensity(50f32, 0f32),
+
+            tooltip_background: Color::from_intensity(0f32, 255f32),
+            tooltip_text_color: Color::from_intensity(255f32, 255f32),
+
+            text_selection_color: Color::from_intensity(255f32, 80f32),
+
+            scrollbar_track_inner: Color::from_intensity(0f32, 32f32),
+            scrollbar_track_outer: Color::from_intensity(0f32, 92f32),
+            scrollbar_thumb_inner: Color::from_intensity(220f32, 100f32),
+            scrollbar_thumb_outer: Color::from_intensity(128f32, 100f32),
         }
     }
 
@@ -178,6 +217,9 @@ impl Theme {
     impl_get_set!(standard_font_size, u32);
     impl_get_set!(button_font_size, u32);
     impl_get_set!(textbox_font_size, u32);
+    impl_get_set!(window_title_font_size, u32);
+    impl_get_set_clone!(standard_font, String);
+    impl_get_set_clone!(window_title_font, String);
     impl_get_set!(window_corner_radius, u32);
     impl_get_set!(window_header_height, u32);
     impl_get_set!(window_dropshadow_size, u32);
@@ -212,4 +254,14 @@ impl Theme {
 
     impl_get_set!(window_popup, Color);
     impl_get_set!(window_popup_transparent, Color);
+
+    impl_get_set!(tooltip_background, Color);
+    impl_get_set!(tooltip_text_color, Color);
+
+    impl_get_set!(text_selection_color, Color);
+
+    impl_get_set!(scrollbar_track_inner, Color);
+    impl_get_set!(scrollbar_track_outer, Color);
+    impl_get_set!(scrollbar_thumb_inner, Color);
+    impl_get_set!(scrollbar_thumb_outer, Color);
 }
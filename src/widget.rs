@@ -13,6 +13,7 @@ use theme::Theme;
 use layout::Layout;
 use window::Window;
 use screen::Screen;
+use widget_container::draw_children;
 
 pub struct WidgetObj {
     pub parent: Option<Weak<RefCell<Widget>>>,
@@ -59,6 +60,7 @@ pub trait Widget {
     fn set_visible(&mut self, bool);
     fn focused(&self) -> bool;
     fn set_focused(&mut self, bool);
+    fn mouse_focus(&self) -> bool;
     fn layout(&self) -> Option<&Box<Layout>>;
     fn set_layout(&mut self, Option<Box<Layout>>);
     fn cursor(&self) -> Cursor;
@@ -67,21 +69,29 @@ pub trait Widget {
     // misc
     fn absolute_position(&self) -> (u32, u32);
     fn visible_recursive(&self) -> bool;
+    fn enabled_recursive(&self) -> bool;
     fn contains(&self, p: (u32, u32)) -> bool;
+    fn scroll_offset(&self) -> (u32, u32);
+    fn clip_to_bounds(&self) -> bool;
     fn request_focus(&self);
+    fn accepts_text_input(&self) -> bool;
+    /// The caret as last drawn, (x, y, width, height) in local coordinates. Input
+    /// methods place their candidate window next to it. None without a caret.
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)>;
     fn preferred_size(&self, &nanovg::Context) -> (u32, u32);
     fn perform_layout(&self, &nanovg::Context);
     fn draw(&self, nanovg_context: &nanovg::Context);
 
     // events
     fn mouse_button_event(&self, (u32, u32), Mouse, bool, SDL_Keymod) -> bool;
-    fn mouse_motion_event(&self, (u32, u32), (u32, u32), Mouse, SDL_Keymod) -> bool;
-    fn mouse_drag_event(&self, (u32, u32), (u32, u32), Mouse, SDL_Keymod) -> bool;
+    fn mouse_motion_event(&self, (u32, u32), (i32, i32), Mouse, SDL_Keymod) -> bool;
+    fn mouse_drag_event(&self, (u32, u32), (i32, i32), Mouse, SDL_Keymod) -> bool;
     fn mouse_enter_event(&mut self, (u32, u32), bool) -> bool;
-    fn scroll_event(&self, (u32, u32), (u32, u32)) -> bool;
+    fn scroll_event(&self, (u32, u32), (i32, i32)) -> bool;
     fn focus_event(&mut self, bool) -> bool;
     fn keyboard_event(&self, Mod, Option<Scancode>, bool, SDL_Keymod) -> bool;
-    fn keyboard_character_event(&self, u32) -> bool;
+    fn text_input_event(&self, &str) -> bool;
+    fn text_editing_event(&self, &str, i32, i32) -> bool;
 
 
     // casts
@@ -90,9 +100,27 @@ pub trait Widget {
 
     // widget_container functions:
     //fn push_child()
+    //insert_child_at()
     //remove_child_by_child()
     //remove_child_by_id()
+    //child_index()
+    //move_child_to_front()
+    //move_child_to_back()
+    //swap_children()
+    //find_rc()
+    //request_redraw()
+    //frame_time()
+    //show_overlay()
+    //clipboard_text()
+    //set_clipboard_text()
     //fn find_widget()
+    //update_hover_path()
+    //find_widget_path()
+    //find_child_path()
+    //content_origin()
+    //draw_origin()
+    //visible_rect()
+    //draw_children()
 }
 
 impl PartialEq for Widget {
@@ -215,6 +243,10 @@ impl Widget for WidgetObj {
         self.focused = focused;
     }
 
+    fn mouse_focus(&self) -> bool {
+        self.mouse_focus
+    }
+
     fn layout(&self) -> Option<&Box<Layout>> {
         self.layout.as_ref()
     }
@@ -283,15 +315,12 @@ impl Widget for WidgetObj {
         if cfg!(feature = "draw-widget-box") {
             nanovg_context.stroke_width(1.0);
             nanovg_context.begin_path();
-            nanovg_context.rect(self.pos.0 as f32 - 0.5, self.pos.1 as f32 - 0.5, self.size.0 as f32 + 1.0, self.size.1 as f32 + 1.0);
+            nanovg_context.rect(-0.5, -0.5, self.size.0 as f32 + 1.0, self.size.1 as f32 + 1.0);
             nanovg_context.stroke_color(nanovg::Color::rgba(255, 0, 0, 255));
             nanovg_context.stroke();
         }
 
-        for child in &self.children {
-            let p_mut = child.borrow_mut();
-            p_mut.draw(nanovg_context);
-        }
+        draw_children(self, nanovg_context);
     }
 
     fn visible_recursive(&self) -> bool {
@@ -308,11 +337,37 @@ impl Widget for WidgetObj {
         return true;
     }
 
+    fn enabled_recursive(&self) -> bool {
+        if !self.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
     fn contains(&self, p: (u32, u32)) -> bool {
         return p.0 >= self.pos.0 && p.1 >= self.pos.1 && p.0 < self.pos.0 + self.size.0 && p.1 < self.pos.1 + self.size.1;
     }
 
+    fn scroll_offset(&self) -> (u32, u32) {
+        (0, 0)
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        true
+    }
+
     fn request_focus(&self) {
+        if !self.enabled_recursive() {
+            return
+        }
+
         let mut widget: Option<Weak<RefCell<Widget>>> = self.parent.clone();
         loop {
             if let Some(val) = widget {
@@ -335,13 +390,27 @@ impl Widget for WidgetObj {
         }
     }
 
+    fn accepts_text_input(&self) -> bool {
+        false
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        None
+    }
+
     // events
 
+    // The release of a drag and the motion that leaves a child are delivered even
+    // when the mouse is left of or above the widget, so positions are clamped.
     fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
-        let adjusted_pos = (p.0 - self.pos.0, p.1 - self.pos.1);
+        let adjusted_pos = (p.0.saturating_sub(self.pos.0), p.1.saturating_sub(self.pos.1));
+
+        for child in self.children.iter().rev() {
+            if !child.borrow().visible() || !child.borrow().enabled() {
+                continue
+            }
 
-        for child in &self.children {
-            if child.borrow().visible() && child.borrow().contains(adjusted_pos) && child.borrow().mouse_button_event(adjusted_pos, button, down, mods) {
+            if child.borrow().contains(adjusted_pos) && child.borrow().mouse_button_event(adjusted_pos, button, down, mods) {
                 return true;
             }
         }
@@ -358,21 +427,19 @@ impl Widget for WidgetObj {
         return false;
     }
 
-    fn mouse_motion_event(&self, p: (u32, u32), rel: (u32, u32), button: Mouse, mods: SDL_Keymod) -> bool {
-        let adjusted_pos = (p.0 - self.pos.0, p.1 - self.pos.1);
-        let prev_adjusted_pos = (p.0 - self.pos.0 - rel.0, p.1 - self.pos.1 - rel.0);
+    // Enter/leave events are delivered by the Screen, which tracks the hover path.
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        let adjusted_pos = (p.0.saturating_sub(self.pos.0), p.1.saturating_sub(self.pos.1));
+        let prev_adjusted_pos = (adjusted_pos.0 as i32 - rel.0, adjusted_pos.1 as i32 - rel.1);
 
-        for child in &self.children {
-            if !child.borrow().visible() {
+        for child in self.children.iter().rev() {
+            if !child.borrow().visible() || !child.borrow().enabled() {
                 continue
             }
 
             let contained: bool = child.borrow().contains(adjusted_pos);
-            let prev_contained: bool = child.borrow().contains(prev_adjusted_pos);
-
-            if contained != prev_contained {
-                child.borrow_mut().mouse_enter_event(p, contained);
-            }
+            let prev_contained: bool = prev_adjusted_pos.0 >= 0 && prev_adjusted_pos.1 >= 0 &&
+                child.borrow().contains((prev_adjusted_pos.0 as u32, prev_adjusted_pos.1 as u32));
 
             if (contained || prev_contained) && child.borrow().mouse_motion_event(adjusted_pos, rel, button, mods) {
                 return true;
@@ -382,7 +449,7 @@ impl Widget for WidgetObj {
         return false;
     }
 
-    fn mouse_drag_event(&self, _: (u32, u32), _: (u32, u32), _: Mouse, _: SDL_Keymod) -> bool {
+    fn mouse_drag_event(&self, _: (u32, u32), _: (i32, i32), _: Mouse, _: SDL_Keymod) -> bool {
         false
     }
 
@@ -391,11 +458,11 @@ impl Widget for WidgetObj {
         false
     }
 
-    fn scroll_event(&self, p: (u32, u32), rel: (u32, u32)) -> bool {
-        let adjusted_pos = (p.0 - self.pos.0, p.1 - self.pos.1);
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        let adjusted_pos = (p.0.saturating_sub(self.pos.0), p.1.saturating_sub(self.pos.1));
 
-        for child in &self.children {
-            if !child.borrow().visible() {
+        for child in self.children.iter().rev() {
+            if !child.borrow().visible() || !child.borrow().enabled() {
                 continue
             }
 
@@ -416,7 +483,11 @@ impl Widget for WidgetObj {
         false
     }
 
-    fn keyboard_character_event(&self, _: u32) -> bool {
+    fn text_input_event(&self, _: &str) -> bool {
+        false
+    }
+
+    fn text_editing_event(&self, _: &str, _: i32, _: i32) -> bool {
         false
     }
 
@@ -462,4 +533,12 @@ impl WidgetObj {
             cursor: Cursor::Arrow
         }
     }
+
+    /// The font the widget draws its text with, the standard font of its theme.
+    pub fn font(&self) -> String {
+        match self.theme {
+            Some(ref theme_val) => theme_val.borrow().standard_font(),
+            None => "sans".to_string()
+        }
+    }
 }
@@ -1,32 +1,38 @@
 extern crate nanovg;
 
+use std::cmp;
 use std::rc::Rc;
 use std::cell::RefCell;
 use widget::Widget;
 
-pub fn push_child(container: Rc<RefCell<Widget>>, new_child: Rc<RefCell<Widget>>) {
+// Children are kept in z-order: the first child is at the back, the last child
+// is drawn last (on top of its siblings) and is the first to be hit-tested.
+
+fn detach_child(container: Rc<RefCell<Widget>>, new_child: Rc<RefCell<Widget>>) {
+    let child_id: String;
+    let mut parent: Option<Rc<RefCell<Widget>>> = None;
     {
-        let child_id: String;
-        let mut parent: Option<Rc<RefCell<Widget>>> = None;
-        {
-            let new_child_borrow = new_child.borrow();
-            child_id = new_child_borrow.id().clone();
-
-            if let Some(ref val) = new_child_borrow.parent() {
-                if let Some(val_upgraded) = val.upgrade() {
-                    parent = Some(val_upgraded);
-                }
+        let new_child_borrow = new_child.borrow();
+        child_id = new_child_borrow.id().clone();
+
+        if let Some(ref val) = new_child_borrow.parent() {
+            if let Some(val_upgraded) = val.upgrade() {
+                parent = Some(val_upgraded);
             }
         }
+    }
 
-        if container.borrow().id() == child_id {
-            panic!("Do not add a widget to itself.");
-        }
+    if container.borrow().id() == child_id {
+        panic!("Do not add a widget to itself.");
+    }
 
-        if let Some(ref val) = parent {
-            remove_child_by_id(val.clone(), child_id);
-        }
+    if let Some(ref val) = parent {
+        remove_child_by_id(val.clone(), child_id);
     }
+}
+
+pub fn push_child(container: Rc<RefCell<Widget>>, new_child: Rc<RefCell<Widget>>) {
+    detach_child(container.clone(), new_child.clone());
     unsafe {
         new_child.borrow_mut().set_parent(Some(container.clone()));
     }
@@ -35,14 +41,30 @@ pub fn push_child(container: Rc<RefCell<Widget>>, new_child: Rc<RefCell<Widget>>
     }
 }
 
+/// Inserts `new_child` at `index`, shifting later siblings back. An index past the
+/// end appends the child. If the child already belongs to `container`, the index
+/// refers to the sibling order after it has been taken out.
+pub fn insert_child_at(container: Rc<RefCell<Widget>>, index: usize, new_child: Rc<RefCell<Widget>>) {
+    detach_child(container.clone(), new_child.clone());
+    unsafe {
+        new_child.borrow_mut().set_parent(Some(container.clone()));
+    }
+    unsafe {
+        let mut container_borrow = container.borrow_mut();
+        let children = container_borrow.children_mut();
+        let index = if index > children.len() { children.len() } else { index };
+        children.insert(index, new_child.clone());
+    }
+}
+
 pub fn remove_child_by_id(container: Rc<RefCell<Widget>>, id: String) {
     let position: Option<usize>;
     {
-        position = container.borrow().children().iter().position(|x| x.borrow().id() == id);
+        position = child_index(container.clone(), id);
     }
     if let Some(index) = position {
         unsafe {
-            let removed_child = container.borrow_mut().children_mut().swap_remove(index).clone();
+            let removed_child = container.borrow_mut().children_mut().remove(index).clone();
             removed_child.borrow_mut().set_parent(None);
         }
     }
@@ -56,18 +78,319 @@ pub fn remove_child_by_child(container: Rc<RefCell<Widget>>, child: Rc<RefCell<W
     remove_child_by_id(container, id);
 }
 
+pub fn child_index(container: Rc<RefCell<Widget>>, id: String) -> Option<usize> {
+    let container_borrow = container.borrow();
+    let children = container_borrow.children();
+    children.iter().position(|x| x.borrow().id() == id)
+}
+
+/// Moves the child to the end of the list, so it is drawn on top of its siblings.
+pub fn move_child_to_front(container: Rc<RefCell<Widget>>, id: String) {
+    if let Some(index) = child_index(container.clone(), id) {
+        unsafe {
+            let mut container_borrow = container.borrow_mut();
+            let children = container_borrow.children_mut();
+            let child = children.remove(index);
+            children.push(child);
+        }
+    }
+}
+
+/// Moves the child to the start of the list, so it is drawn below its siblings.
+pub fn move_child_to_back(container: Rc<RefCell<Widget>>, id: String) {
+    if let Some(index) = child_index(container.clone(), id) {
+        unsafe {
+            let mut container_borrow = container.borrow_mut();
+            let children = container_borrow.children_mut();
+            let child = children.remove(index);
+            children.insert(0, child);
+        }
+    }
+}
+
+pub fn swap_children(container: Rc<RefCell<Widget>>, first: usize, second: usize) {
+    unsafe {
+        let mut container_borrow = container.borrow_mut();
+        let children = container_borrow.children_mut();
+        if first >= children.len() || second >= children.len() {
+            panic!("Child index out of bounds.");
+        }
+        children.swap(first, second);
+    }
+}
+
+/// Returns the `Rc` its parent holds for `widget`, or `None` for widgets without a parent.
+pub fn find_rc(widget: &Widget) -> Option<Rc<RefCell<Widget>>> {
+    if let Some(val) = widget.parent() {
+        if let Some(parent) = val.upgrade() {
+            let id = widget.id();
+            return parent.borrow().children().into_iter().find(|x| x.borrow().id() == id);
+        }
+    }
+
+    None
+}
+
+// Walks up from `widget` to the screen it belongs to, if it is attached to one.
+fn find_screen(widget: &Widget) -> Option<Rc<RefCell<Widget>>> {
+    let mut current = match widget.parent() {
+        Some(val) => val.upgrade(),
+        None => None
+    };
+
+    while let Some(val) = current {
+        if val.borrow().as_screen().is_some() {
+            return Some(val)
+        }
+
+        current = match val.borrow().parent() {
+            Some(parent) => parent.upgrade(),
+            None => None
+        };
+    }
+
+    None
+}
+
+/// Asks the screen `widget` belongs to for a new frame. Safe to call while drawing.
+pub fn request_redraw(widget: &Widget) {
+    if let Some(val) = find_screen(widget) {
+        if let Some(screen) = val.borrow().as_screen() {
+            screen.request_redraw();
+        }
+    }
+}
+
+/// The frame clock of the screen `widget` belongs to, see Screen::frame_time.
+pub fn frame_time(widget: &Widget) -> Option<u32> {
+    match find_screen(widget) {
+        Some(val) => val.borrow().as_screen().map(|screen| screen.frame_time()),
+        None => None
+    }
+}
+
+/// Shows `overlay` above all windows of the screen `widget` belongs to, see
+/// Screen::show_overlay. Returns false when the widget is not attached to a screen.
+pub fn show_overlay(widget: &Widget, overlay: Rc<RefCell<Widget>>) -> bool {
+    if let Some(val) = find_screen(widget) {
+        if let Some(screen) = val.borrow().as_screen() {
+            screen.show_overlay(overlay, widget.id());
+            return true
+        }
+    }
+
+    false
+}
+
+/// Reads the clipboard of the screen `widget` belongs to. Safe to call from events.
+pub fn clipboard_text(widget: &Widget) -> Option<String> {
+    match find_screen(widget) {
+        Some(val) => match val.borrow().as_screen() {
+            Some(screen) => screen.clipboard_text(),
+            None => None
+        },
+        None => None
+    }
+}
+
+/// Puts `text` on the clipboard of the screen `widget` belongs to. Returns false
+/// when the widget is not attached to a screen.
+pub fn set_clipboard_text(widget: &Widget, text: &str) -> bool {
+    if let Some(val) = find_screen(widget) {
+        if let Some(screen) = val.borrow().as_screen() {
+            screen.set_clipboard_text(text);
+            return true
+        }
+    }
+
+    false
+}
+
+/// The point on the screen the positions of the children of `widget` are relative
+/// to: its absolute position less the scroll offsets of itself and its ancestors.
+/// Negative when the content is scrolled past the top or left of the screen.
+pub fn content_origin(widget: &Widget) -> (i32, i32) {
+    let (parent_x, parent_y) = match widget.parent() {
+        Some(val) => match val.upgrade() {
+            Some(parent) => content_origin(&*parent.borrow()),
+            None => (0, 0)
+        },
+        None => (0, 0)
+    };
+
+    let (pos, offset) = (widget.pos(), widget.scroll_offset());
+    (parent_x + pos.0 as i32 - offset.0 as i32, parent_y + pos.1 as i32 - offset.1 as i32)
+}
+
+/// Converts the screen point `p` into the coordinates of the parent of `widget`,
+/// which mouse events are given in. Points left of or above the parent's content
+/// are clamped to 0.
+pub fn to_parent_coords(widget: &Widget, p: (u32, u32)) -> (u32, u32) {
+    let (origin_x, origin_y) = match widget.parent() {
+        Some(val) => match val.upgrade() {
+            Some(parent) => content_origin(&*parent.borrow()),
+            None => (0, 0)
+        },
+        None => (0, 0)
+    };
+
+    (cmp::max(p.0 as i32 - origin_x, 0) as u32, cmp::max(p.1 as i32 - origin_y, 0) as u32)
+}
+
+// The overlap of two (x, y, width, height) rectangles, None when they do not overlap.
+fn intersect_rects(a: (i32, i32, i32, i32), b: (i32, i32, i32, i32)) -> Option<(i32, i32, i32, i32)> {
+    let (left, top) = (cmp::max(a.0, b.0), cmp::max(a.1, b.1));
+    let (right, bottom) = (cmp::min(a.0 + a.2, b.0 + b.2), cmp::min(a.1 + a.3, b.1 + b.3));
+    if right <= left || bottom <= top {
+        return None
+    }
+
+    Some((left, top, right - left, bottom - top))
+}
+
+/// The point on the screen the local (0, 0) of `widget` is drawn at. The draw
+/// traversal translates by the position of every child and scroll panels by their
+/// scroll offset, so this is the absolute position moved by the scroll offsets of
+/// the ancestors.
+pub fn draw_origin(widget: &Widget) -> (i32, i32) {
+    let pos = widget.pos();
+    match widget.parent() {
+        Some(val) => match val.upgrade() {
+            Some(parent) => {
+                let origin = content_origin(&*parent.borrow());
+                (origin.0 + pos.0 as i32, origin.1 + pos.1 as i32)
+            },
+            None => (pos.0 as i32, pos.1 as i32)
+        },
+        None => (pos.0 as i32, pos.1 as i32)
+    }
+}
+
+/// The part of `widget` that can be seen, as (x, y, width, height) in screen
+/// coordinates: its bounds at its draw_origin, cut down to the visible parts of
+/// its ancestors. None when it is scrolled or placed out of view.
+pub fn visible_rect(widget: &Widget) -> Option<(i32, i32, i32, i32)> {
+    let (origin, size) = (draw_origin(widget), widget.size());
+    let bounds = (origin.0, origin.1, size.0 as i32, size.1 as i32);
+    let parent = match widget.parent() {
+        Some(val) => val.upgrade(),
+        None => None
+    };
+
+    match parent {
+        Some(val) => match visible_rect(&*val.borrow()) {
+            Some(parent_rect) => intersect_rects(bounds, parent_rect),
+            None => None
+        },
+        // intersecting with itself only drops empty bounds
+        None => intersect_rects(bounds, bounds)
+    }
+}
+
+/// Draws the visible children of `container` in z-order, expecting the transform
+/// to be at the local (0, 0) of the container. Each child is drawn translated to its
+/// position, so it draws in its own local coordinates, and with the scissor narrowed
+/// to its bounds, so it cannot paint over its neighbours, unless its clip_to_bounds
+/// returns false; such widgets have to clip their own children. Clipped children
+/// that lie outside the visible part of the container are not drawn at all.
+pub fn draw_children(container: &Widget, nanovg_context: &nanovg::Context) {
+    let region = match visible_rect(container) {
+        Some(val) => val,
+        None => return
+    };
+    let origin = content_origin(container);
+
+    for child in container.children() {
+        let child_borrow = child.borrow();
+        if !child_borrow.visible() {
+            continue;
+        }
+
+        let (pos, size) = (child_borrow.pos(), child_borrow.size());
+        let clip = child_borrow.clip_to_bounds();
+        let bounds = (origin.0 + pos.0 as i32, origin.1 + pos.1 as i32, size.0 as i32, size.1 as i32);
+        if clip && intersect_rects(bounds, region).is_none() {
+            continue;
+        }
+
+        nanovg_context.save();
+        nanovg_context.translate(pos.0 as f32, pos.1 as f32);
+        if clip {
+            nanovg_context.intersect_scissor(0.0, 0.0, size.0 as f32, size.1 as f32);
+        }
+        child_borrow.draw(nanovg_context);
+        nanovg_context.restore();
+    }
+}
+
+/// Returns the top-most widget under `p`, which is given in the parent coordinates
+/// of `container` (the same space `contains` uses).
 pub fn find_widget(container: Rc<RefCell<Widget>>, p: (u32, u32)) -> Option<Rc<RefCell<Widget>>> {
-    let borrow_container = container.borrow();
-    for child in &borrow_container.children() {
+    find_widget_path(container, p).pop()
+}
+
+/// Returns every widget under `p` from `container` down to the top-most leaf. Later
+/// siblings are tested first, points outside a widget never reach its children and
+/// a disabled widget is never part of the path, so the hit resolves to its nearest
+/// enabled ancestor.
+pub fn find_widget_path(container: Rc<RefCell<Widget>>, p: (u32, u32)) -> Vec<Rc<RefCell<Widget>>> {
+    let local_p: (u32, u32);
+    {
+        let borrow_container = container.borrow();
+        if !borrow_container.visible() || !borrow_container.enabled() || !borrow_container.contains(p) {
+            return Vec::new();
+        }
+        local_p = (p.0 - borrow_container.pos().0, p.1 - borrow_container.pos().1);
+    }
+
+    let mut path = vec![container.clone()];
+    path.extend(find_child_path(&*container.borrow(), local_p));
+    path
+}
+
+/// Like `find_widget_path`, but `p` is local to `container` and the container
+/// itself is not included in the path. The children are hit-tested where they
+/// are shown, i.e. moved by the scroll offset of the container.
+pub fn find_child_path(container: &Widget, p: (u32, u32)) -> Vec<Rc<RefCell<Widget>>> {
+    let mut path = Vec::new();
+    let offset = container.scroll_offset();
+    let p = (p.0 + offset.0, p.1 + offset.1);
+
+    for child in container.children().iter().rev() {
         let borrow_child = child.borrow();
-        let new_p = (p.0 - borrow_container.pos().0, p.1 - borrow_container.pos().1);
-        if borrow_child.visible() && borrow_child.contains(new_p) {
-            return find_widget(child.clone(), new_p);
+        if !borrow_child.visible() || !borrow_child.contains(p) {
+            continue;
         }
+
+        if borrow_child.enabled() {
+            let new_p = (p.0 - borrow_child.pos().0, p.1 - borrow_child.pos().1);
+            path.push(child.clone());
+            path.extend(find_child_path(&*borrow_child, new_p));
+        }
+        break;
     }
 
-    return match borrow_container.contains(p) {
-        true => Some(container.clone()),
-        false => None
+    path
+}
+
+/// Sends mouse_enter_event(false) to widgets of `old_path` that are not part of
+/// `new_path` (innermost first) and mouse_enter_event(true) to widgets that were
+/// not hovered before (outermost first). Widgets on both paths receive nothing.
+pub fn update_hover_path(old_path: &[Rc<RefCell<Widget>>], new_path: &[Rc<RefCell<Widget>>], p: (u32, u32)) {
+    let old_ids: Vec<String> = old_path.iter().map(|x| x.borrow().id()).collect();
+    let new_ids: Vec<String> = new_path.iter().map(|x| x.borrow().id()).collect();
+
+    for (widget, id) in old_path.iter().zip(old_ids.iter()).rev() {
+        if !new_ids.contains(id) {
+            let local = to_parent_coords(&*widget.borrow(), p);
+            widget.borrow_mut().mouse_enter_event(local, false);
+        }
+    }
+
+    for (widget, id) in new_path.iter().zip(new_ids.iter()) {
+        if !old_ids.contains(id) {
+            let local = to_parent_coords(&*widget.borrow(), p);
+            widget.borrow_mut().mouse_enter_event(local, true);
+        }
     }
 }
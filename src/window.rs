@@ -124,6 +124,10 @@ impl Widget for Window {
         self.widget.focused = focused;
     }
 
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus
+    }
+
     fn layout(&self) -> Option<&Box<Layout>> {
         self.widget.layout.as_ref()
     }
@@ -142,20 +146,86 @@ impl Widget for Window {
 
     // misc
 
-    fn perform_layout(&self, _: &nanovg::Context) {
-        // TODO
+    // The layout gets the Window itself rather than its WidgetObj, so it can make
+    // room for the header.
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        match self.widget.layout {
+            Some(ref val) => val.perform_layout(nanovg_context, self),
+            None => self.widget.perform_layout(nanovg_context)
+        }
     }
 
-    fn preferred_size(&self, _: &nanovg::Context) -> (u32, u32) {
-        // TODO
-        (0, 0)
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        match self.widget.layout {
+            Some(ref val) => val.preferred_size(nanovg_context, self),
+            None => self.widget.size
+        }
     }
 
     fn draw(&self, nanovg_context: &nanovg::Context) {
-        for child in &self.widget.children {
-            let p_mut = child.borrow_mut();
-            p_mut.draw(nanovg_context);
+        if let Some(ref theme_val) = self.widget.theme {
+            let theme = theme_val.borrow();
+            let (w, h) = (self.widget.size.0 as f32, self.widget.size.1 as f32);
+            let ds = theme.window_dropshadow_size() as f32;
+            let cr = theme.window_corner_radius() as f32;
+            let hh = theme.window_header_height() as f32;
+
+            nanovg_context.save();
+
+            // drop shadow, drawn first so the window body covers its inner part
+            let shadow_paint = nanovg_context.box_gradient(0.0, 2.0, w, h, cr * 2.0, ds * 2.0,
+                theme.dropshadow().to_nanovg_color(), theme.transparent().to_nanovg_color());
+            nanovg_context.begin_path();
+            nanovg_context.rect(-ds, -ds, w + 2.0 * ds, h + 2.0 * ds);
+            nanovg_context.fill_paint(shadow_paint);
+            nanovg_context.fill();
+
+            nanovg_context.begin_path();
+            nanovg_context.rounded_rect(0.0, 0.0, w, h, cr);
+            if self.widget.focused {
+                nanovg_context.fill_color(theme.window_fill_focused().to_nanovg_color());
+            } else {
+                nanovg_context.fill_color(theme.window_fill_unfocused().to_nanovg_color());
+            }
+            nanovg_context.fill();
+
+            if self.title.len() > 0 {
+                let header_paint = nanovg_context.linear_gradient(0.0, 0.0, 0.0, hh,
+                    theme.window_header_gradient_top().to_nanovg_color(), theme.window_header_gradient_bot().to_nanovg_color());
+                nanovg_context.begin_path();
+                nanovg_context.rounded_rect(0.0, 0.0, w, hh, cr);
+                nanovg_context.fill_paint(header_paint);
+                nanovg_context.fill();
+
+                nanovg_context.begin_path();
+                nanovg_context.move_to(0.5, hh - 1.5);
+                nanovg_context.line_to(w - 0.5, hh - 1.5);
+                nanovg_context.stroke_width(1.0);
+                nanovg_context.stroke_color(theme.window_header_sep_bot().to_nanovg_color());
+                nanovg_context.stroke();
+
+                let title_color = if !self.widget.enabled_recursive() {
+                    theme.disabled_text_color()
+                } else if self.widget.focused {
+                    theme.window_title_focused()
+                } else {
+                    theme.window_title_unfocused()
+                };
+
+                nanovg_context.font_size(theme.window_title_font_size() as f32);
+                nanovg_context.font_face(&theme.window_title_font());
+                nanovg_context.text_align(nanovg::CENTER | nanovg::MIDDLE);
+                nanovg_context.fill_color(title_color.to_nanovg_color());
+                nanovg_context.text(w / 2.0, hh / 2.0, &self.title);
+            }
+
+            nanovg_context.restore();
         }
+
+        nanovg_context.save();
+        nanovg_context.intersect_scissor(0.0, 0.0, self.widget.size.0 as f32, self.widget.size.1 as f32);
+        self.widget.draw(nanovg_context);
+        nanovg_context.restore();
     }
 
     fn absolute_position(&self) -> (u32, u32) {
@@ -183,14 +253,45 @@ impl Widget for Window {
         return true;
     }
 
+    fn enabled_recursive(&self) -> bool {
+        if !self.widget.enabled {
+            return false
+        }
+
+        if let Some(ref val) = self.widget.parent {
+            if let Some(ref val_upgraded) = val.upgrade() {
+                return val_upgraded.borrow().enabled_recursive();
+            }
+        }
+
+        return true;
+    }
+
     fn contains(&self, p: (u32, u32)) -> bool {
         return p.0 >= self.widget.pos.0 && p.1 >= self.widget.pos.1 && p.0 < self.widget.pos.0 + self.widget.size.0 && p.1 < self.widget.pos.1 + self.widget.size.1;
     }
 
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    // the drop shadow reaches past the bounds, the children are clipped in draw
+    fn clip_to_bounds(&self) -> bool {
+        false
+    }
+
     fn request_focus(&self) {
         self.widget.request_focus();
     }
 
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
     // events
     // TODO 
 
@@ -198,11 +299,11 @@ impl Widget for Window {
         self.widget.mouse_button_event(p, button, down, mods)
     }
 
-    fn mouse_motion_event(&self, p: (u32, u32), rel: (u32, u32), button: Mouse, mods: SDL_Keymod) -> bool {
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
         self.widget.mouse_motion_event(p, rel, button, mods)
     }
 
-    fn mouse_drag_event(&self, p: (u32, u32), rel: (u32, u32), button: Mouse, mods: SDL_Keymod) -> bool {
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
         self.widget.mouse_drag_event(p, rel, button, mods)
     }
 
@@ -210,7 +311,7 @@ impl Widget for Window {
         self.widget.mouse_enter_event(p, enter)
     }
 
-    fn scroll_event(&self, p: (u32, u32), rel: (u32, u32)) -> bool {
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
         self.widget.scroll_event(p, rel)
     }
 
@@ -222,8 +323,12 @@ impl Widget for Window {
         self.widget.keyboard_event(key, scancode, pressed, mods)
     }
 
-    fn keyboard_character_event(&self, codepoint: u32) -> bool {
-        self.widget.keyboard_character_event(codepoint)
+    fn text_input_event(&self, text: &str) -> bool {
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
     }
 
     // casts
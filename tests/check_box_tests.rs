@@ -0,0 +1,55 @@
+extern crate nanoguirustsdl;
+extern crate nanovg;
+extern crate sdl2;
+
+use nanoguirustsdl::check_box::{CheckBox, CheckState};
+use nanoguirustsdl::widget::Widget;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::mouse::Mouse;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn check_box_click_test() {
+    let check_box = CheckBox::new("check".to_string(), "Check".to_string());
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let changes_clone = changes.clone();
+    check_box.borrow_mut().set_size((100, 20));
+    check_box.borrow_mut().set_on_change(Some(Box::new(move |x: bool| changes_clone.borrow_mut().push(x))));
+
+    check_box.borrow().mouse_button_event((10, 10), Mouse::Left, true, 0);
+    assert!(check_box.borrow().pushed());
+    assert!(!check_box.borrow().checked());
+    check_box.borrow().mouse_button_event((10, 10), Mouse::Left, false, 0);
+    assert!(!check_box.borrow().pushed());
+    assert!(check_box.borrow().checked());
+
+    // releasing outside the box cancels the click
+    check_box.borrow().mouse_button_event((10, 10), Mouse::Left, true, 0);
+    check_box.borrow().mouse_button_event((150, 10), Mouse::Left, false, 0);
+    assert!(check_box.borrow().checked());
+
+    check_box.borrow_mut().set_state(CheckState::Indeterminate);
+    check_box.borrow_mut().focus_event(true);
+    assert!(check_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Space), true, 0));
+    assert_eq!(check_box.borrow().state(), CheckState::Checked);
+    check_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Space), true, 0);
+    assert_eq!(check_box.borrow().state(), CheckState::Unchecked);
+
+    assert_eq!(*changes.borrow(), vec![true, true, false]);
+}
+
+#[test]
+fn check_box_preferred_size_test() {
+    let check_box = CheckBox::new("check".to_string(), "Check".to_string());
+    check_box.borrow_mut().set_font_size(Some(20));
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+
+    let (width, height) = check_box.borrow().preferred_size(&vg);
+    let text_width = vg.text_bounds(0.0, 0.0, "Check", None);
+    assert_eq!(width, (text_width + 36.0) as u32);
+    assert_eq!(height, 26);
+
+    check_box.borrow_mut().set_fixed_size((80, 0));
+    assert_eq!(check_box.borrow().preferred_size(&vg), (80, 26));
+}
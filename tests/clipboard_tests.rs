@@ -0,0 +1,68 @@
+extern crate nanoguirustsdl;
+extern crate sdl2;
+
+use nanoguirustsdl::clipboard::{Clipboard, MemoryClipboard};
+use nanoguirustsdl::label::Label;
+use nanoguirustsdl::screen::Screen;
+use nanoguirustsdl::widget::Widget;
+use nanoguirustsdl::widget_container::push_child;
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod, Scancode, LCTRLMOD};
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn memory_clipboard_test() {
+    let clipboard = MemoryClipboard::new();
+    let mut writer = clipboard.clone();
+
+    assert_eq!(clipboard.text(), None);
+
+    writer.set_text("copied");
+    assert_eq!(clipboard.text(), Some("copied".to_string()));
+
+    writer.set_text("");
+    assert_eq!(clipboard.text(), Some("".to_string()));
+}
+
+#[test]
+fn label_copy_needs_screen_test() {
+    let label = Label::new("label".to_string(), "caption".to_string(), "sans".to_string(), None);
+    label.borrow_mut().set_selectable(true);
+    label.borrow_mut().focus_event(true);
+
+    // without a screen there is no clipboard to copy to
+    assert!(!label.borrow().keyboard_event(Mod::empty(), Some(Scancode::C), true, LCTRLMOD.bits()));
+}
+
+// Presses Ctrl+C on a screen where a focused label is the only widget and
+// returns what ended up on the clipboard.
+fn copy_from_label(selectable: bool) -> Option<String> {
+    let clipboard = MemoryClipboard::new();
+    let screen = Screen::new_debug("screen".to_string(), (200, 200));
+    screen.borrow_mut().set_clipboard(Box::new(clipboard.clone()));
+    let label = Label::new("label".to_string(), "caption".to_string(), "sans".to_string(), None);
+    label.borrow_mut().set_selectable(selectable);
+    push_child(screen.clone(), label.clone());
+    let focused: Rc<RefCell<Widget>> = label.clone();
+    Screen::set_focus(screen.clone(), Some(focused));
+
+    let copy = Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(Keycode::C), scancode: Some(Scancode::C), keymod: LCTRLMOD, repeat: false };
+    assert_eq!(Screen::handle_event(screen.clone(), &copy), selectable);
+    clipboard.text()
+}
+
+#[test]
+fn label_copy_test() {
+    assert_eq!(copy_from_label(true), Some("caption".to_string()));
+    assert_eq!(copy_from_label(false), None);
+}
+
+#[test]
+fn debug_screen_clipboard_test() {
+    let screen = Screen::new_debug("screen".to_string(), (200, 200));
+    assert_eq!(screen.borrow().clipboard_text(), None);
+
+    screen.borrow().set_clipboard_text("copied");
+    assert_eq!(screen.borrow().clipboard_text(), Some("copied".to_string()));
+}
@@ -0,0 +1,106 @@
+extern crate nanoguirustsdl;
+extern crate nanovg;
+extern crate sdl2;
+
+use nanoguirustsdl::combo_box::ComboBox;
+use nanoguirustsdl::scroll_panel::VScrollPanel;
+use nanoguirustsdl::widget::{Widget, WidgetObj};
+use nanoguirustsdl::widget_container::push_child;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::mouse::Mouse;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+fn numbered_items(count: usize) -> Vec<(String, String)> {
+    (0..count).map(|x| (format!("{}", x), format!("Item number {}", x))).collect()
+}
+
+#[test]
+fn combo_box_selection_test() {
+    let combo_box = ComboBox::new("combo".to_string());
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let changes_clone = changes.clone();
+    combo_box.borrow_mut().set_items(numbered_items(3));
+    combo_box.borrow_mut().set_on_change(Some(Box::new(move |x: usize| changes_clone.borrow_mut().push(x))));
+    assert_eq!(combo_box.borrow().caption(), "0");
+
+    // the wheel steps through the items, stopping at both ends
+    assert!(combo_box.borrow().scroll_event((0, 0), (0, -1)));
+    combo_box.borrow().scroll_event((0, 0), (0, -1));
+    combo_box.borrow().scroll_event((0, 0), (0, -1));
+    assert_eq!(combo_box.borrow().selected_index(), 2);
+    assert_eq!(combo_box.borrow().caption(), "2");
+
+    combo_box.borrow_mut().focus_event(true);
+    assert!(combo_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Up), true, 0));
+    assert_eq!(combo_box.borrow().selected_index(), 1);
+
+    // selecting from code does not call on_change
+    combo_box.borrow_mut().set_selected_index(0);
+    combo_box.borrow_mut().set_selected_index(5);
+    assert_eq!(combo_box.borrow().selected_index(), 0);
+    assert_eq!(*changes.borrow(), vec![1, 2, 1]);
+}
+
+#[test]
+fn combo_box_popup_test() {
+    let combo_box = ComboBox::new("combo".to_string());
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let changes_clone = changes.clone();
+    combo_box.borrow_mut().set_items(numbered_items(20));
+    combo_box.borrow_mut().set_font_size(Some(16));
+    combo_box.borrow_mut().set_pos((10, 30));
+    combo_box.borrow_mut().set_size((120, 26));
+    combo_box.borrow_mut().set_on_change(Some(Box::new(move |x: usize| changes_clone.borrow_mut().push(x))));
+
+    let popup = combo_box.borrow().popup();
+    assert!(!popup.borrow().visible());
+
+    // the list opens below the combo box and shows 8 rows of 24px
+    combo_box.borrow().mouse_button_event((20, 40), Mouse::Left, true, 0);
+    assert!(combo_box.borrow().popup_visible());
+    assert!(popup.borrow().visible());
+    assert_eq!(popup.borrow().pos(), (10, 56));
+    assert_eq!(popup.borrow().size(), (120, 8 * 24 + 8));
+
+    // scrolling the list moves it by whole rows, then clicking the third row selects
+    assert!(popup.borrow().scroll_event((20, 100), (0, -5)));
+    popup.borrow().mouse_button_event((20, 56 + 4 + 2 * 24 + 10), Mouse::Left, true, 0);
+    assert!(!combo_box.borrow().popup_visible());
+    assert_eq!(combo_box.borrow().selected_index(), 7);
+
+    // Escape closes the list, losing the focus as well
+    combo_box.borrow_mut().focus_event(true);
+    combo_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Return), true, 0);
+    assert!(combo_box.borrow().popup_visible());
+    assert!(combo_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Escape), true, 0));
+    assert!(!combo_box.borrow().popup_visible());
+    combo_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Space), true, 0);
+    combo_box.borrow_mut().focus_event(false);
+    assert!(!popup.borrow().visible());
+
+    assert_eq!(*changes.borrow(), vec![7]);
+}
+
+#[test]
+fn combo_box_in_scroll_panel_test() {
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    let panel = VScrollPanel::new("panel".to_string());
+    let content = Rc::new(RefCell::new(WidgetObj::new("content".to_string())));
+    let combo_box = ComboBox::new("combo".to_string());
+    panel.borrow_mut().set_pos((20, 20));
+    panel.borrow_mut().set_size((200, 100));
+    content.borrow_mut().set_fixed_size((180, 400));
+    combo_box.borrow_mut().set_items(numbered_items(3));
+    combo_box.borrow_mut().set_font_size(Some(16));
+    combo_box.borrow_mut().set_pos((10, 150));
+    combo_box.borrow_mut().set_size((120, 26));
+    push_child(panel.clone(), content.clone());
+    push_child(content.clone(), combo_box.clone());
+    panel.borrow().perform_layout(&vg);
+    panel.borrow_mut().set_scroll((0.0, 120.0));
+
+    // the list opens right below the combo box as it is shown in the panel
+    combo_box.borrow().set_popup_visible(true);
+    assert_eq!(combo_box.borrow().popup().borrow().pos(), (20 + 10, 20 + 150 - 120 + 26));
+}
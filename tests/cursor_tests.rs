@@ -0,0 +1,88 @@
+extern crate nanoguirustsdl;
+extern crate sdl2;
+
+use nanoguirustsdl::common::Cursor;
+use nanoguirustsdl::cursor::{CursorManager, RecordingCursorBackend, SdlCursorBackend};
+use nanoguirustsdl::screen::Screen;
+use nanoguirustsdl::widget::{Widget, WidgetObj};
+use nanoguirustsdl::widget_container::push_child;
+use sdl2::event::Event;
+use sdl2::mouse::{Mouse, MouseState};
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn cursor_manager_only_forwards_changes_test() {
+    let backend = RecordingCursorBackend::new();
+    let mut manager = CursorManager::new(Box::new(backend.clone()));
+
+    assert_eq!(manager.current(), None);
+    assert_eq!(backend.last_cursor(), None);
+
+    manager.update(Cursor::Arrow);
+    manager.update(Cursor::Arrow);
+    assert_eq!(backend.request_count(), 1);
+
+    manager.update(Cursor::IBeam);
+    assert_eq!(manager.current(), Some(Cursor::IBeam));
+    assert_eq!(backend.last_cursor(), Some(Cursor::IBeam));
+    assert_eq!(backend.request_count(), 2);
+}
+
+#[test]
+fn sdl_cursor_backend_without_video_test() {
+    // the system cursors are only created once one is set
+    let mut manager = CursorManager::new(Box::new(SdlCursorBackend::new()));
+    manager.update(Cursor::IBeam);
+    assert_eq!(manager.current(), Some(Cursor::IBeam));
+}
+
+fn motion(p: (i32, i32)) -> Event {
+    Event::MouseMotion { timestamp: 0, window_id: 0, which: 0, mousestate: MouseState::from_sdl_state(0), x: p.0, y: p.1, xrel: 0, yrel: 0 }
+}
+
+fn button(p: (i32, i32), down: bool) -> Event {
+    if down {
+        Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn: Mouse::Left, x: p.0, y: p.1 }
+    } else {
+        Event::MouseButtonUp { timestamp: 0, window_id: 0, which: 0, mouse_btn: Mouse::Left, x: p.0, y: p.1 }
+    }
+}
+
+#[test]
+fn cursor_of_hovered_widget_test() {
+    let backend = RecordingCursorBackend::new();
+    let screen = Screen::new_debug("screen".to_string(), (100, 100));
+    screen.borrow_mut().set_cursor_backend(Box::new(backend.clone()));
+    let text = Rc::new(RefCell::new(WidgetObj::new("text".to_string())));
+    text.borrow_mut().set_pos((10, 10));
+    text.borrow_mut().set_size((20, 20));
+    text.borrow_mut().set_cursor(Cursor::IBeam);
+    push_child(screen.clone(), text.clone());
+
+    Screen::handle_event(screen.clone(), &motion((15, 15)));
+    assert_eq!(backend.last_cursor(), Some(Cursor::IBeam));
+    Screen::handle_event(screen.clone(), &motion((50, 50)));
+    assert_eq!(backend.last_cursor(), Some(Cursor::Arrow));
+    assert_eq!(backend.request_count(), 2);
+}
+
+#[test]
+fn cursor_of_dragged_widget_test() {
+    let backend = RecordingCursorBackend::new();
+    let screen = Screen::new_debug("screen".to_string(), (100, 100));
+    screen.borrow_mut().set_cursor_backend(Box::new(backend.clone()));
+    let text = Rc::new(RefCell::new(WidgetObj::new("text".to_string())));
+    text.borrow_mut().set_pos((10, 10));
+    text.borrow_mut().set_size((20, 20));
+    text.borrow_mut().set_cursor(Cursor::IBeam);
+    push_child(screen.clone(), text.clone());
+
+    // the dragged widget keeps its cursor while the mouse is outside of it
+    Screen::handle_event(screen.clone(), &button((15, 15), true));
+    Screen::handle_event(screen.clone(), &motion((50, 50)));
+    assert_eq!(backend.last_cursor(), Some(Cursor::IBeam));
+
+    Screen::handle_event(screen.clone(), &button((50, 50), false));
+    assert_eq!(backend.last_cursor(), Some(Cursor::Arrow));
+}
@@ -4,6 +4,8 @@ extern crate nanovg;
 use nanoguirustsdl::widget::{Widget, WidgetObj};
 use nanoguirustsdl::widget_container::push_child;
 use nanoguirustsdl::layout::{BoxLayout, Orientation};
+use nanoguirustsdl::window::Window;
+use nanoguirustsdl::theme::Theme;
 use std::rc::Rc;
 use std::cell::RefCell;
 
@@ -86,3 +88,26 @@ fn preferred_size_with_children_of_children_test() {
     let result = widget_one.borrow().preferred_size(&vg);
     assert_eq!(result, (14u32, 14u32));
 }
+
+#[test]
+fn window_layout_test() {
+    let window = Window::new("window".to_string(), "Window".to_string());
+    let widget_two = Rc::new(RefCell::new(WidgetObj::new("two".to_string())));
+    let theme = Rc::new(RefCell::new(Theme::new_debug()));
+    let mut layout = BoxLayout::new(Orientation::Vertical);
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+
+    layout.margin = 2;
+    window.borrow_mut().set_theme(Some(theme.clone()));
+    window.borrow_mut().set_layout(Some(Box::new(layout)));
+    push_child(window.clone(), widget_two.clone());
+    widget_two.borrow_mut().set_size((10, 10));
+
+    // the window header is added on top of the children
+    let result = window.borrow().preferred_size(&vg);
+    assert_eq!(result, (14u32, 43u32));
+
+    window.borrow_mut().set_size(result);
+    window.borrow().perform_layout(&vg);
+    assert_eq!(widget_two.borrow().pos(), (2u32, 31u32));
+}
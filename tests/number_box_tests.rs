@@ -0,0 +1,99 @@
+extern crate nanoguirustsdl;
+extern crate sdl2;
+
+use nanoguirustsdl::number_box::{FloatBox, IntBox, Numeric};
+use nanoguirustsdl::widget::Widget;
+use sdl2::keyboard::{Mod, Scancode};
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn int_box_clamp_test() {
+    let int_box = IntBox::new("int".to_string(), 5i32);
+    int_box.borrow_mut().set_min(Some(0));
+    int_box.borrow_mut().set_max(Some(10));
+
+    int_box.borrow_mut().set_value(42);
+    assert_eq!(int_box.borrow().value(), 10);
+    assert_eq!(int_box.borrow().text_box().value(), "10");
+
+    int_box.borrow_mut().set_value(-3);
+    assert_eq!(int_box.borrow().value(), 0);
+}
+
+#[test]
+fn int_box_typed_value_test() {
+    let int_box = IntBox::new("int".to_string(), 5u8);
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let changes_clone = changes.clone();
+    int_box.borrow_mut().set_max(Some(100));
+    int_box.borrow_mut().set_on_change(Some(Box::new(move |x: u8| changes_clone.borrow_mut().push(x))));
+    int_box.borrow_mut().focus_event(true);
+
+    int_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Backspace), true, 0);
+    int_box.borrow().text_input_event("250");
+    assert!(int_box.borrow().text_box().valid());
+    int_box.borrow_mut().focus_event(false);
+
+    assert_eq!(int_box.borrow().value(), 100);
+    assert_eq!(int_box.borrow().text_box().value(), "100");
+
+    int_box.borrow_mut().focus_event(true);
+    int_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Up), true, 0);
+    assert_eq!(int_box.borrow().value(), 100);
+    int_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Down), true, 0);
+    assert_eq!(*changes.borrow(), vec![100, 99]);
+
+    // text that does not parse is reverted
+    int_box.borrow().text_input_event("x");
+    assert!(!int_box.borrow().text_box().valid());
+    int_box.borrow_mut().focus_event(false);
+    assert_eq!(int_box.borrow().value(), 99);
+}
+
+#[test]
+fn float_box_spin_test() {
+    let float_box = FloatBox::new("float".to_string(), 0.5f32);
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let changes_clone = changes.clone();
+    float_box.borrow_mut().set_step(0.25);
+    float_box.borrow_mut().set_decimals(Some(1));
+    float_box.borrow_mut().set_on_change(Some(Box::new(move |x: f32| changes_clone.borrow_mut().push(x))));
+    assert_eq!(float_box.borrow().text_box().value(), "0.5");
+
+    // scrolling only changes spinnable boxes
+    assert!(!float_box.borrow().scroll_event((0, 0), (0, 1)));
+    float_box.borrow_mut().set_spinnable(true);
+    assert!(float_box.borrow().scroll_event((0, 0), (0, 2)));
+
+    assert_eq!(float_box.borrow().value(), 1.0);
+    assert_eq!(float_box.borrow().text_box().value(), "1.0");
+    assert_eq!(*changes.borrow(), vec![1.0]);
+}
+
+#[test]
+fn int_box_limits_test() {
+    // stepping below zero or past the maximum saturates instead of wrapping
+    let int_box = IntBox::new("int".to_string(), 0u32);
+    int_box.borrow_mut().set_spinnable(true);
+    assert!(int_box.borrow().scroll_event((0, 0), (0, -1)));
+    assert_eq!(int_box.borrow().value(), 0);
+
+    int_box.borrow_mut().set_value(u32::max_value() - 1);
+    assert!(int_box.borrow().scroll_event((0, 0), (0, 3)));
+    assert_eq!(int_box.borrow().value(), u32::max_value());
+
+    // values past 2^53 are stepped without losing digits
+    let big = (1u64 << 60) + 1;
+    assert_eq!(big.add_steps(1, 2), big + 2);
+    assert_eq!(i64::max_value().add_steps(5, -1), i64::max_value() - 5);
+
+    // huge step counts are multiplied, not looped over
+    assert_eq!(i32::min_value().add_steps(1, i32::max_value()), -1);
+    assert_eq!(250u8.add_steps(10, i32::max_value()), 255);
+    assert_eq!(3u8.add_steps(1, i32::min_value()), 0);
+    assert_eq!((-100i8).add_steps(1, 200), 100);
+    assert_eq!((-128i8).add_steps(2, i32::max_value()), 127);
+    assert_eq!(<u8 as Numeric>::from_f64(-1.0), 0);
+    assert_eq!(<i8 as Numeric>::from_f64(1000.0), 127);
+}
@@ -0,0 +1,92 @@
+extern crate nanoguirustsdl;
+extern crate nanovg;
+extern crate sdl2;
+
+use nanoguirustsdl::popup::{Popup, PopupSide};
+use nanoguirustsdl::popup_button::PopupButton;
+use nanoguirustsdl::widget::{Widget, WidgetObj};
+use nanoguirustsdl::scroll_panel::VScrollPanel;
+use nanoguirustsdl::widget_container::push_child;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::mouse::Mouse;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn popup_placement_test() {
+    let screen = Rc::new(RefCell::new(WidgetObj::new("screen".to_string())));
+    let anchor = Rc::new(RefCell::new(WidgetObj::new("anchor".to_string())));
+    let popup = Popup::new("popup".to_string());
+    screen.borrow_mut().set_size((300, 100));
+    anchor.borrow_mut().set_pos((50, 20));
+    anchor.borrow_mut().set_size((40, 20));
+    push_child(screen.clone(), popup.clone());
+
+    popup.borrow_mut().set_anchor(Some(anchor.clone()));
+    popup.borrow_mut().set_size((100, 60));
+    assert_eq!(popup.borrow().placed_side(), PopupSide::Right);
+    assert_eq!(popup.borrow().pos(), (50 + 40 + 15, 0));
+
+    // there is no room on the right, so the popup flips to the left
+    anchor.borrow_mut().set_pos((150, 60));
+    popup.borrow_mut().set_size((100, 80));
+    assert_eq!(popup.borrow().placed_side(), PopupSide::Left);
+    assert_eq!(popup.borrow().pos(), (150 - 100 - 15, 20));
+    assert_eq!(popup.borrow().absolute_position(), (35, 20));
+
+    // a popup preferring the left side flips when the anchor is too close to the edge
+    anchor.borrow_mut().set_pos((10, 20));
+    popup.borrow_mut().set_side(PopupSide::Left);
+    popup.borrow_mut().update_position();
+    assert_eq!(popup.borrow().placed_side(), PopupSide::Right);
+}
+
+#[test]
+fn popup_button_toggle_test() {
+    let button = PopupButton::new("button".to_string(), "More".to_string());
+    let popup = button.borrow().popup();
+    button.borrow_mut().set_pos((10, 10));
+    button.borrow_mut().set_size((80, 26));
+    popup.borrow_mut().set_size((120, 90));
+    assert!(!button.borrow().popup_visible());
+    assert_eq!(popup.borrow().anchor().map(|x| x.borrow().id()), Some("button".to_string()));
+
+    button.borrow().mouse_button_event((20, 20), Mouse::Left, true, 0);
+    assert!(button.borrow().popup_visible());
+    assert_eq!(popup.borrow().pos(), (10 + 80 + 15, 0));
+    button.borrow().mouse_button_event((20, 20), Mouse::Left, true, 0);
+    assert!(!popup.borrow().visible());
+
+    button.borrow_mut().focus_event(true);
+    assert!(button.borrow().keyboard_event(Mod::empty(), Some(Scancode::Space), true, 0));
+    assert!(button.borrow().popup_visible());
+    assert!(button.borrow().keyboard_event(Mod::empty(), Some(Scancode::Escape), true, 0));
+    assert!(!button.borrow().popup_visible());
+    assert!(!button.borrow().keyboard_event(Mod::empty(), Some(Scancode::Escape), true, 0));
+}
+
+#[test]
+fn popup_anchor_in_scroll_panel_test() {
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    let screen = Rc::new(RefCell::new(WidgetObj::new("screen".to_string())));
+    let panel = VScrollPanel::new("panel".to_string());
+    let content = Rc::new(RefCell::new(WidgetObj::new("content".to_string())));
+    let anchor = Rc::new(RefCell::new(WidgetObj::new("anchor".to_string())));
+    let popup = Popup::new("popup".to_string());
+    screen.borrow_mut().set_size((300, 300));
+    panel.borrow_mut().set_size((100, 100));
+    content.borrow_mut().set_fixed_size((80, 300));
+    anchor.borrow_mut().set_pos((10, 150));
+    anchor.borrow_mut().set_size((40, 20));
+    push_child(screen.clone(), panel.clone());
+    push_child(panel.clone(), content.clone());
+    push_child(content.clone(), anchor.clone());
+    push_child(screen.clone(), popup.clone());
+    panel.borrow().perform_layout(&vg);
+    panel.borrow_mut().set_scroll((0.0, 100.0));
+
+    // the popup follows the anchor to where it is shown, 100px above its absolute position
+    popup.borrow_mut().set_anchor(Some(anchor.clone()));
+    popup.borrow_mut().set_size((100, 60));
+    assert_eq!(popup.borrow().pos(), (10 + 40 + 15, 60 - 30));
+}
@@ -0,0 +1,30 @@
+extern crate nanoguirustsdl;
+
+use nanoguirustsdl::progress_bar::ProgressBar;
+
+#[test]
+fn progress_bar_value_test() {
+    let progress_bar = ProgressBar::new("progress".to_string());
+    progress_bar.borrow_mut().set_value(0.42);
+    assert_eq!(progress_bar.borrow().percentage_text(), "42%");
+    assert_eq!(progress_bar.borrow().bar(100.0, 0), (0.0, 42.0));
+
+    progress_bar.borrow_mut().set_value(1.5);
+    assert_eq!(progress_bar.borrow().value(), 1.0);
+    assert_eq!(progress_bar.borrow().percentage_text(), "100%");
+
+    // the clock does not affect a determinate bar
+    assert_eq!(progress_bar.borrow().bar(100.0, 700), (0.0, 100.0));
+}
+
+#[test]
+fn progress_bar_indeterminate_test() {
+    let progress_bar = ProgressBar::new("progress".to_string());
+    progress_bar.borrow_mut().set_indeterminate(true);
+
+    // a 25px segment sweeps across 100px every 1500ms
+    assert_eq!(progress_bar.borrow().bar(100.0, 0), (0.0, 0.0));
+    assert_eq!(progress_bar.borrow().bar(100.0, 750), (38.0, 25.0));
+    assert_eq!(progress_bar.borrow().bar(100.0, 1440), (95.0, 5.0));
+    assert_eq!(progress_bar.borrow().bar(100.0, 1500 + 750), (38.0, 25.0));
+}
@@ -0,0 +1,29 @@
+extern crate nanoguirustsdl;
+extern crate sdl2;
+
+use nanoguirustsdl::screen_manager::{ScreenManager, event_window_id};
+use sdl2::event::{Event, WindowEvent};
+
+#[test]
+fn event_window_id_test() {
+    let close = Event::Window { timestamp: 0, window_id: 3, win_event: WindowEvent::Close };
+    let text = Event::TextInput { timestamp: 0, window_id: 7, text: "a".to_string() };
+    let quit = Event::Quit { timestamp: 0 };
+
+    assert_eq!(event_window_id(&close), Some(3));
+    assert_eq!(event_window_id(&text), Some(7));
+    assert_eq!(event_window_id(&quit), None);
+}
+
+#[test]
+fn screen_manager_without_screens_test() {
+    let mut manager = ScreenManager::new();
+    let close = Event::Window { timestamp: 0, window_id: 3, win_event: WindowEvent::Close };
+
+    assert!(manager.add_font("sans".to_string(), "Roboto-Regular.ttf".to_string()).is_ok());
+    assert!(!manager.handle_event(&close));
+    assert!(manager.screen(3).is_none());
+    assert!(manager.remove_screen(3).is_none());
+    assert!(!manager.needs_redraw());
+    assert_eq!(manager.redraw_timeout(), None);
+}
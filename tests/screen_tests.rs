@@ -0,0 +1,323 @@
+extern crate nanoguirustsdl;
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+mod support;
+
+use nanoguirustsdl::screen::{Screen, TooltipTiming, TOOLTIP_FADE_DURATION, framebuffer_metrics, tooltip_timing};
+use nanoguirustsdl::scroll_panel::VScrollPanel;
+use nanoguirustsdl::shortcut::{KeyChord, ShortcutScope};
+use nanoguirustsdl::text_box::TextBox;
+use nanoguirustsdl::theme::Theme;
+use nanoguirustsdl::widget::{Widget, WidgetObj};
+use nanoguirustsdl::widget_container::push_child;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod, Scancode};
+use sdl2::mouse::{Mouse, MouseState};
+use support::Probe;
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+
+fn key_down(keycode: Keycode) -> Event {
+    Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(keycode), scancode: None, keymod: Mod::empty(), repeat: false }
+}
+
+fn text_input(text: &str) -> Event {
+    Event::TextInput { timestamp: 0, window_id: 0, text: text.to_string() }
+}
+
+// Dispatched like any other event, but handled by nobody.
+fn exposed() -> Event {
+    Event::Window { timestamp: 0, window_id: 0, win_event: WindowEvent::Exposed }
+}
+
+fn text_box(id: &str, pos: (u32, u32)) -> Rc<RefCell<TextBox>> {
+    let text_box = TextBox::new(id.to_string(), String::new());
+    text_box.borrow_mut().set_theme(Some(Rc::new(RefCell::new(Theme::new_debug()))));
+    text_box.borrow_mut().set_pos(pos);
+    text_box.borrow_mut().set_size((100, 24));
+    text_box.borrow_mut().set_fixed_size((100, 24));
+    text_box
+}
+
+// A screen where F1 and F2 are bound and every run of their actions is recorded.
+fn screen_with_shortcuts(triggered: Rc<RefCell<Vec<Keycode>>>) -> Rc<RefCell<Screen>> {
+    let screen = Screen::new_debug("screen".to_string(), (200, 200));
+    for &key in &[Keycode::F1, Keycode::F2] {
+        let triggered_clone = triggered.clone();
+        screen.borrow_mut().shortcuts_mut().register(KeyChord::new(key), ShortcutScope::Global, Box::new(move || {
+            triggered_clone.borrow_mut().push(key);
+        })).unwrap();
+    }
+    screen
+}
+
+#[test]
+fn debug_screen_test() {
+    let screen = Screen::new_debug("screen".to_string(), (200, 100));
+    assert!(screen.borrow().nanovg_context().is_none());
+    assert_eq!(screen.borrow().window_id(), 0);
+    assert!(!screen.borrow().make_current());
+    assert_eq!(screen.borrow().size(), (200, 100));
+    assert_eq!(screen.borrow().framebuffer_size(), (200, 100));
+
+    // the clock stands still unless one is set
+    assert_eq!(screen.borrow().frame_time(), 0);
+    let now = Rc::new(Cell::new(250));
+    let now_clone = now.clone();
+    screen.borrow_mut().set_clock(Box::new(move || now_clone.get()));
+    assert!(screen.borrow().draw_all());
+    assert_eq!(screen.borrow().frame_time(), 250);
+}
+
+#[test]
+fn pre_event_callback_consumes_test() {
+    let triggered = Rc::new(RefCell::new(Vec::new()));
+    let screen = screen_with_shortcuts(triggered.clone());
+    let unhandled = Rc::new(Cell::new(0));
+
+    // the hook keeps its own count and consumes every second event
+    let mut seen = 0;
+    screen.borrow_mut().set_pre_event_callback(Some(Box::new(move |_: &Event| {
+        seen += 1;
+        seen % 2 == 0
+    })));
+    let unhandled_clone = unhandled.clone();
+    let mut unhandled_count = 0;
+    screen.borrow_mut().set_unhandled_event_callback(Some(Box::new(move |_: &Event| {
+        unhandled_count += 1;
+        unhandled_clone.set(unhandled_count);
+    })));
+
+    assert!(Screen::handle_event(screen.clone(), &key_down(Keycode::F1)));
+    assert!(Screen::handle_event(screen.clone(), &key_down(Keycode::F2)));
+    assert_eq!(*triggered.borrow(), vec![Keycode::F1]);
+
+    // events nothing handled reach the unhandled-event callback, consumed ones do not
+    assert!(!Screen::handle_event(screen.clone(), &key_down(Keycode::A)));
+    assert!(Screen::handle_event(screen.clone(), &key_down(Keycode::A)));
+    assert_eq!(unhandled.get(), 1);
+}
+
+#[test]
+fn process_events_disabled_test() {
+    let triggered = Rc::new(RefCell::new(Vec::new()));
+    let screen = screen_with_shortcuts(triggered.clone());
+    let resized = Rc::new(Cell::new((0, 0)));
+    let resized_clone = resized.clone();
+    screen.borrow_mut().set_resize_callback(Some(Box::new(move |size: (u32, u32)| resized_clone.set(size))));
+    screen.borrow_mut().set_process_events(false);
+
+    assert!(!Screen::handle_event(screen.clone(), &key_down(Keycode::F1)));
+    assert_eq!(*triggered.borrow(), vec![]);
+
+    // window events are still processed
+    let resize = Event::Window { timestamp: 0, window_id: 0, win_event: WindowEvent::Resized(300, 250) };
+    assert!(Screen::handle_event(screen.clone(), &resize));
+    assert_eq!(resized.get(), (300, 250));
+    assert_eq!(screen.borrow().size(), (300, 250));
+
+    screen.borrow_mut().set_process_events(true);
+    assert!(Screen::handle_event(screen.clone(), &key_down(Keycode::F1)));
+    assert_eq!(*triggered.borrow(), vec![Keycode::F1]);
+}
+
+#[test]
+fn shortcut_with_focused_text_box_test() {
+    let triggered = Rc::new(RefCell::new(Vec::new()));
+    let screen = screen_with_shortcuts(triggered.clone());
+    let text = text_box("text", (10, 10));
+    push_child(screen.clone(), text.clone());
+    let focused: Rc<RefCell<Widget>> = text.clone();
+    Screen::set_focus(screen.clone(), Some(focused));
+
+    // keys the box does not handle reach the shortcuts
+    let f1 = Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(Keycode::F1), scancode: Some(Scancode::F1), keymod: Mod::empty(), repeat: false };
+    assert!(Screen::handle_event(screen.clone(), &f1));
+    assert_eq!(*triggered.borrow(), vec![Keycode::F1]);
+
+    // keys it does handle stay in the box
+    assert!(Screen::handle_event(screen.clone(), &text_input("ab")));
+    let left = Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(Keycode::Left), scancode: Some(Scancode::Left), keymod: Mod::empty(), repeat: false };
+    assert!(Screen::handle_event(screen.clone(), &left));
+    assert_eq!(text.borrow().cursor_pos(), 1);
+    assert_eq!(*triggered.borrow(), vec![Keycode::F1]);
+}
+
+#[test]
+fn text_input_routing_test() {
+    let screen = Screen::new_debug("screen".to_string(), (200, 200));
+    let first = text_box("first", (10, 10));
+    let second = text_box("second", (10, 50));
+    push_child(screen.clone(), first.clone());
+    push_child(screen.clone(), second.clone());
+
+    let focused: Rc<RefCell<Widget>> = second.clone();
+    Screen::set_focus(screen.clone(), Some(focused));
+    assert!(screen.borrow().text_input_active());
+    // nothing was drawn yet, so the candidate window goes over the whole box
+    assert_eq!(screen.borrow().text_input_rect(), Some((10, 50, 100, 24)));
+
+    // text and compositions only reach the focused widget
+    assert!(Screen::handle_event(screen.clone(), &text_input("ab")));
+    let editing = Event::TextEditing { timestamp: 0, window_id: 0, text: "c".to_string(), start: 1, length: 0 };
+    assert!(Screen::handle_event(screen.clone(), &editing));
+    assert_eq!(second.borrow().editing_value(), "ab".to_string());
+    assert_eq!(first.borrow().editing_value(), "".to_string());
+
+    // once drawn, the candidate window follows the caret behind the composition
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    screen.borrow().draw(&vg);
+    let caret = second.borrow().caret_rect().unwrap();
+    Screen::handle_event(screen.clone(), &exposed());
+    assert_eq!(screen.borrow().text_input_rect(), Some(((10.0 + caret.0) as i32, (50.0 + caret.1) as i32, caret.2.ceil() as i32, caret.3.ceil() as i32)));
+
+    Screen::set_focus(screen.clone(), None);
+    assert!(!screen.borrow().text_input_active());
+    assert_eq!(screen.borrow().text_input_rect(), None);
+    assert!(!Screen::handle_event(screen.clone(), &text_input("x")));
+}
+
+#[test]
+fn text_input_rect_scroll_test() {
+    let screen = Screen::new_debug("screen".to_string(), (200, 200));
+    let panel = VScrollPanel::new("panel".to_string());
+    let content = Rc::new(RefCell::new(WidgetObj::new("content".to_string())));
+    let text = text_box("text", (0, 100));
+    panel.borrow_mut().set_size((100, 100));
+    content.borrow_mut().set_fixed_size((80, 300));
+    push_child(screen.clone(), panel.clone());
+    push_child(panel.clone(), content.clone());
+    push_child(content.clone(), text.clone());
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    screen.borrow().perform_layout(&vg);
+
+    let focused: Rc<RefCell<Widget>> = text.clone();
+    Screen::set_focus(screen.clone(), Some(focused));
+    assert_eq!(screen.borrow().text_input_rect(), Some((0, 100, 100, 24)));
+
+    panel.borrow_mut().set_scroll((0.0, 40.0));
+    Screen::handle_event(screen.clone(), &exposed());
+    assert_eq!(screen.borrow().text_input_rect(), Some((0, 60, 100, 24)));
+}
+
+#[test]
+fn tooltip_timing_test() {
+    assert_eq!(tooltip_timing(1000, 1000, 500), TooltipTiming::Waiting(500));
+    assert_eq!(tooltip_timing(1000, 1499, 500), TooltipTiming::Waiting(1));
+    assert_eq!(tooltip_timing(1000, 1500, 500), TooltipTiming::FadingIn(0.0));
+    assert_eq!(tooltip_timing(1000, 1500 + TOOLTIP_FADE_DURATION / 2, 500), TooltipTiming::FadingIn(0.5));
+    assert_eq!(tooltip_timing(1000, 1500 + TOOLTIP_FADE_DURATION, 500), TooltipTiming::Shown);
+
+    // an interaction after the frame started counts as no time passed
+    assert_eq!(tooltip_timing(1000, 900, 500), TooltipTiming::Waiting(500));
+    assert_eq!(tooltip_timing(1000, 1000, 0), TooltipTiming::FadingIn(0.0));
+}
+
+#[test]
+fn ui_scale_test() {
+    let screen = Screen::new_debug("screen".to_string(), (200, 100));
+    let probe = Probe::new("probe", (10, 10), (20, 20));
+    push_child(screen.clone(), probe.clone());
+
+    for &scale in &[0.0, -1.0, std::f32::NAN, std::f32::INFINITY] {
+        assert!(Screen::set_ui_scale(screen.clone(), scale).is_err());
+    }
+    assert_eq!(screen.borrow().ui_scale(), 1.0);
+    assert_eq!(screen.borrow().size(), (200, 100));
+
+    assert!(Screen::set_ui_scale(screen.clone(), 2.0).is_ok());
+    assert_eq!(screen.borrow().size(), (100, 50));
+    assert_eq!(screen.borrow().framebuffer_size(), (200, 100));
+    assert_eq!(screen.borrow().pixel_ratio(), 1.0);
+
+    // mouse positions arrive in window coordinates and are scaled down
+    let click = Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn: Mouse::Left, x: 30, y: 30 };
+    Screen::handle_event(screen.clone(), &click);
+    let outside = Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn: Mouse::Left, x: 15, y: 15 };
+    Screen::handle_event(screen.clone(), &outside);
+    assert_eq!(*probe.borrow().button_events.borrow(), vec![((15, 15), true)]);
+
+    let resize = Event::Window { timestamp: 0, window_id: 0, win_event: WindowEvent::Resized(300, 200) };
+    Screen::handle_event(screen.clone(), &resize);
+    assert_eq!(screen.borrow().size(), (150, 100));
+    assert_eq!(screen.borrow().framebuffer_size(), (300, 200));
+}
+
+#[test]
+fn framebuffer_metrics_test() {
+    // a high-DPI display with twice as many pixels as window units
+    assert_eq!(framebuffer_metrics((100, 50), (200, 100), 1.0), ((200, 100), 2.0, (100, 50)));
+    assert_eq!(framebuffer_metrics((300, 150), (600, 300), 1.5), ((600, 300), 2.0, (200, 100)));
+
+    // an unknown drawable size or an empty window do not scale
+    assert_eq!(framebuffer_metrics((100, 50), (0, 0), 1.0), ((100, 50), 1.0, (100, 50)));
+    assert_eq!(framebuffer_metrics((0, 0), (0, 0), 2.0), ((0, 0), 1.0, (0, 0)));
+}
+
+#[test]
+fn redraw_flag_test() {
+    let triggered = Rc::new(RefCell::new(Vec::new()));
+    let screen = screen_with_shortcuts(triggered.clone());
+
+    // a new screen draws its first frame, then waits for changes
+    assert!(screen.borrow().needs_redraw());
+    assert!(screen.borrow().draw_all());
+    assert!(!screen.borrow().needs_redraw());
+    assert!(!screen.borrow().draw_all());
+
+    screen.borrow().request_redraw();
+    assert!(screen.borrow().needs_redraw());
+    assert!(screen.borrow().draw_all());
+    assert!(!screen.borrow().needs_redraw());
+
+    assert!(Screen::handle_event(screen.clone(), &key_down(Keycode::F1)));
+    assert!(screen.borrow().needs_redraw());
+    assert!(screen.borrow().draw_all());
+
+    // events the screen does not process leave it clean
+    Screen::handle_event(screen.clone(), &Event::Quit { timestamp: 0 });
+    screen.borrow_mut().set_process_events(false);
+    Screen::handle_event(screen.clone(), &key_down(Keycode::F2));
+    assert!(!screen.borrow().needs_redraw());
+    assert_eq!(*triggered.borrow(), vec![Keycode::F1]);
+}
+
+#[test]
+fn redraw_timeout_test() {
+    let screen = Screen::new_debug("screen".to_string(), (100, 100));
+    let now = Rc::new(Cell::new(1000));
+    let now_clone = now.clone();
+    screen.borrow_mut().set_clock(Box::new(move || now_clone.get()));
+    let probe = Probe::new("probe", (10, 10), (20, 20));
+    probe.borrow_mut().set_tooltip("tooltip".to_string());
+    push_child(screen.clone(), probe.clone());
+    let motion = |x: i32, y: i32| Event::MouseMotion { timestamp: 0, window_id: 0, which: 0, mousestate: MouseState::from_sdl_state(0), x: x, y: y, xrel: 0, yrel: 0 };
+
+    // nothing is animated while no tooltip is hovered
+    assert_eq!(screen.borrow().redraw_timeout(), None);
+
+    Screen::handle_event(screen.clone(), &motion(15, 15));
+    screen.borrow().draw_all();
+    assert_eq!(screen.borrow().redraw_timeout(), Some(500));
+    now.set(1300);
+    assert_eq!(screen.borrow().redraw_timeout(), Some(200));
+    assert!(!screen.borrow().needs_redraw());
+
+    // every frame of the fade is drawn, plus a last one fully opaque
+    now.set(1500);
+    assert_eq!(screen.borrow().redraw_timeout(), Some(0));
+    assert!(screen.borrow().draw_all());
+    now.set(1500 + TOOLTIP_FADE_DURATION);
+    assert!(screen.borrow().needs_redraw());
+    assert!(screen.borrow().draw_all());
+    assert_eq!(screen.borrow().redraw_timeout(), None);
+    assert!(!screen.borrow().needs_redraw());
+
+    // moving the mouse restarts the delay, leaving the widget ends it
+    Screen::handle_event(screen.clone(), &motion(16, 16));
+    assert_eq!(screen.borrow().redraw_timeout(), Some(500));
+    Screen::handle_event(screen.clone(), &motion(50, 50));
+    assert_eq!(screen.borrow().redraw_timeout(), None);
+}
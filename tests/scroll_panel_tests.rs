@@ -0,0 +1,108 @@
+extern crate nanoguirustsdl;
+extern crate nanovg;
+extern crate sdl2;
+
+use nanoguirustsdl::scroll_panel::VScrollPanel;
+use nanoguirustsdl::widget::{Widget, WidgetObj};
+use nanoguirustsdl::widget_container::{content_origin, draw_origin, find_child_path, push_child, visible_rect};
+use sdl2::mouse::Mouse;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn scroll_panel_layout_test() {
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    let panel = VScrollPanel::new("panel".to_string());
+    let content = Rc::new(RefCell::new(WidgetObj::new("content".to_string())));
+    panel.borrow_mut().set_size((100, 100));
+    content.borrow_mut().set_fixed_size((80, 300));
+    push_child(panel.clone(), content.clone());
+
+    // the child is as wide as the panel without the scrollbar, and as high as it wants
+    panel.borrow().perform_layout(&vg);
+    assert_eq!(panel.borrow().viewport_size(), (88, 100));
+    assert_eq!(content.borrow().size(), (88, 300));
+
+    // the wheel only moves the target, the offset follows while drawing
+    assert!(panel.borrow().scroll_event((50, 50), (0, -2)));
+    assert_eq!(panel.borrow().scroll_target(), (0.0, 80.0));
+    assert_eq!(panel.borrow().scroll(), (0.0, 0.0));
+    panel.borrow().draw(&vg);
+    assert_eq!(panel.borrow().scroll(), (0.0, 80.0));
+
+    panel.borrow_mut().set_scroll((0.0, 500.0));
+    assert_eq!(panel.borrow().scroll_offset(), (0, 200));
+    assert!(!panel.borrow().scroll_event((50, 50), (0, 0)));
+
+    // content that fits does not scroll
+    content.borrow_mut().set_fixed_size((80, 60));
+    panel.borrow().perform_layout(&vg);
+    assert_eq!(panel.borrow().scroll_offset(), (0, 0));
+    assert!(!panel.borrow().scroll_event((50, 50), (0, -1)));
+}
+
+#[test]
+fn scroll_panel_event_translation_test() {
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    let root = Rc::new(RefCell::new(WidgetObj::new("root".to_string())));
+    let panel = VScrollPanel::new("panel".to_string());
+    let content = Rc::new(RefCell::new(WidgetObj::new("content".to_string())));
+    let item = Rc::new(RefCell::new(WidgetObj::new("item".to_string())));
+    root.borrow_mut().set_size((200, 200));
+    panel.borrow_mut().set_pos((10, 10));
+    panel.borrow_mut().set_size((100, 100));
+    content.borrow_mut().set_fixed_size((80, 300));
+    item.borrow_mut().set_pos((0, 200));
+    item.borrow_mut().set_size((50, 20));
+    push_child(root.clone(), panel.clone());
+    push_child(panel.clone(), content.clone());
+    push_child(content.clone(), item.clone());
+    panel.borrow().perform_layout(&vg);
+
+    // the item is below the visible part until the panel scrolls
+    let path = find_child_path(&*root.borrow(), (20, 70));
+    assert_eq!(path.last().map(|x| x.borrow().id()), Some("content".to_string()));
+    assert_eq!(visible_rect(&*item.borrow()), None);
+
+    panel.borrow_mut().set_scroll((0.0, 150.0));
+    let path = find_child_path(&*root.borrow(), (20, 70));
+    let ids: Vec<String> = path.iter().map(|x| x.borrow().id()).collect();
+    assert_eq!(ids, vec!["panel", "content", "item"]);
+    assert_eq!(content_origin(&*content.borrow()), (10, -140));
+    assert_eq!(visible_rect(&*content.borrow()), Some((10, 10, 88, 100)));
+    assert_eq!(visible_rect(&*item.borrow()), Some((10, 60, 50, 20)));
+    // scrolled content is drawn above its absolute position
+    assert_eq!(draw_origin(&*item.borrow()), (10, 60));
+    assert_eq!(item.borrow().absolute_position(), (10, 210));
+
+    // dragging the thumb by 10px scrolls by 10px times the content per track pixel
+    panel.borrow_mut().set_scroll((0.0, 0.0));
+    assert!(panel.borrow().mouse_button_event((10 + 95, 10 + 20), Mouse::Left, true, 0));
+    assert!(panel.borrow().mouse_drag_event((10 + 95, 10 + 30), (0, 10), Mouse::Left, 0));
+    let scroll = panel.borrow().scroll().1;
+    assert!(scroll > 32.0 && scroll < 33.0);
+    assert!(panel.borrow().mouse_button_event((10 + 95, 10 + 30), Mouse::Left, false, 0));
+    assert!(!panel.borrow().mouse_drag_event((10 + 95, 10 + 40), (0, 10), Mouse::Left, 0));
+}
+
+#[test]
+fn scroll_panel_release_outside_test() {
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    let panel = VScrollPanel::new("panel".to_string());
+    let content = Rc::new(RefCell::new(WidgetObj::new("content".to_string())));
+    panel.borrow_mut().set_pos((50, 50));
+    panel.borrow_mut().set_size((100, 100));
+    content.borrow_mut().set_fixed_size((80, 300));
+    push_child(panel.clone(), content.clone());
+    panel.borrow().perform_layout(&vg);
+
+    // the screen sends the release to the dragged panel wherever the mouse is
+    assert!(panel.borrow().mouse_button_event((50 + 95, 50 + 20), Mouse::Left, true, 0));
+    assert!(panel.borrow().mouse_drag_event((20, 10), (-125, -60), Mouse::Left, 0));
+    assert!(panel.borrow().mouse_button_event((20, 10), Mouse::Left, false, 0));
+    assert!(!panel.borrow().mouse_drag_event((20, 10), (0, 10), Mouse::Left, 0));
+    assert_eq!(panel.borrow().scroll(), (0.0, 0.0));
+
+    assert!(panel.borrow().scroll_event((0, 0), (0, -1)));
+    assert_eq!(panel.borrow().scroll_target(), (0.0, 40.0));
+}
@@ -0,0 +1,98 @@
+extern crate nanoguirustsdl;
+extern crate sdl2;
+
+use nanoguirustsdl::screen::Screen;
+use nanoguirustsdl::shortcut::{KeyChord, ShortcutRegistry, ShortcutScope};
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn parse_and_display_chord_test() {
+    let chord: KeyChord = "Ctrl+Shift+S".parse().unwrap();
+    assert!(chord.ctrl && chord.shift && !chord.alt && !chord.gui);
+    assert_eq!(chord.key, Keycode::S);
+    assert_eq!(chord.to_string(), "Ctrl+Shift+S".to_string());
+
+    let chord: KeyChord = "shift + ctrl + s".parse().unwrap();
+    assert_eq!(chord.to_string(), "Ctrl+Shift+S".to_string());
+
+    assert!("Hyper+S".parse::<KeyChord>().is_err());
+    assert!("Ctrl+NotAKey".parse::<KeyChord>().is_err());
+}
+
+#[test]
+fn chord_from_keymod_test() {
+    // left ctrl and right shift
+    let chord = KeyChord::from_keymod(0x0040 | 0x0002, Keycode::S);
+    assert_eq!(chord, "Ctrl+Shift+S".parse().unwrap());
+}
+
+#[test]
+fn conflict_test() {
+    let mut registry = ShortcutRegistry::new();
+    let chord: KeyChord = "Ctrl+S".parse().unwrap();
+
+    assert!(registry.register(chord, ShortcutScope::Global, Box::new(|| {})).is_ok());
+    assert!(registry.register(chord, ShortcutScope::Global, Box::new(|| {})).is_err());
+    assert!(registry.register(chord, ShortcutScope::Window("editor".to_string()), Box::new(|| {})).is_ok());
+
+    assert!(registry.unregister(&chord, &ShortcutScope::Global));
+    assert!(!registry.conflicts(&chord, &ShortcutScope::Global));
+}
+
+#[test]
+fn scoped_shortcut_test() {
+    let mut registry = ShortcutRegistry::new();
+    let chord: KeyChord = "Ctrl+S".parse().unwrap();
+    let triggered = Rc::new(RefCell::new(String::new()));
+
+    for &(name, ref scope) in &[("global", ShortcutScope::Global),
+                                 ("window", ShortcutScope::Window("editor".to_string())),
+                                 ("textbox", ShortcutScope::FocusedWithin("textbox".to_string()))] {
+        let triggered_clone = triggered.clone();
+        registry.register(chord, scope.clone(), Box::new(move || {
+            *triggered_clone.borrow_mut() = name.to_string();
+        })).unwrap();
+    }
+
+    assert!(registry.trigger(&chord, &[]));
+    assert_eq!(*triggered.borrow(), "global".to_string());
+
+    assert!(registry.trigger(&chord, &["editor".to_string(), "panel".to_string()]));
+    assert_eq!(*triggered.borrow(), "window".to_string());
+
+    assert!(registry.trigger(&chord, &["editor".to_string(), "textbox".to_string()]));
+    assert_eq!(*triggered.borrow(), "textbox".to_string());
+
+    assert!(!registry.trigger(&"Ctrl+O".parse().unwrap(), &[]));
+}
+
+fn key_down(keycode: Keycode) -> Event {
+    Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(keycode), scancode: None, keymod: Mod::empty(), repeat: false }
+}
+
+#[test]
+fn register_from_action_test() {
+    let screen = Screen::new_debug("screen".to_string(), (200, 200));
+    let triggered = Rc::new(RefCell::new(0));
+
+    // the action of F1 binds F2, which has to survive the action
+    let screen_weak = Rc::downgrade(&screen);
+    let triggered_clone = triggered.clone();
+    screen.borrow_mut().shortcuts_mut().register(KeyChord::new(Keycode::F1), ShortcutScope::Global, Box::new(move || {
+        let triggered_clone = triggered_clone.clone();
+        let screen = screen_weak.upgrade().unwrap();
+        let result = screen.borrow_mut().shortcuts_mut().register(KeyChord::new(Keycode::F2), ShortcutScope::Global, Box::new(move || {
+            *triggered_clone.borrow_mut() += 1;
+        }));
+        result.unwrap();
+    })).unwrap();
+
+    assert!(Screen::handle_event(screen.clone(), &key_down(Keycode::F1)));
+    assert_eq!(screen.borrow().shortcuts().shortcuts().len(), 2);
+
+    assert!(Screen::handle_event(screen.clone(), &key_down(Keycode::F2)));
+    assert_eq!(*triggered.borrow(), 1);
+}
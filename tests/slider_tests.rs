@@ -0,0 +1,55 @@
+extern crate nanoguirustsdl;
+extern crate sdl2;
+
+use nanoguirustsdl::slider::Slider;
+use nanoguirustsdl::widget::Widget;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::mouse::Mouse;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn slider_drag_test() {
+    let slider = Slider::new("slider".to_string());
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let final_changes = Rc::new(RefCell::new(Vec::new()));
+    let (changes_clone, final_changes_clone) = (changes.clone(), final_changes.clone());
+    slider.borrow_mut().set_pos((10, 0));
+    slider.borrow_mut().set_size((100, 20));
+    slider.borrow_mut().set_range((0.0, 10.0));
+    slider.borrow_mut().set_on_change(Some(Box::new(move |x: f32| changes_clone.borrow_mut().push(x))));
+    slider.borrow_mut().set_on_final_change(Some(Box::new(move |x: f32| final_changes_clone.borrow_mut().push(x))));
+
+    // the track starts 11px into the widget and is 78px wide
+    slider.borrow().mouse_button_event((60, 10), Mouse::Left, true, 0);
+    assert_eq!(slider.borrow().value(), 5.0);
+    slider.borrow().mouse_drag_event((200, 10), (140, 0), Mouse::Left, 0);
+    assert_eq!(slider.borrow().value(), 10.0);
+    slider.borrow().mouse_drag_event((0, 10), (-200, 0), Mouse::Left, 0);
+    slider.borrow().mouse_button_event((0, 10), Mouse::Left, false, 0);
+
+    assert_eq!(*changes.borrow(), vec![5.0, 10.0, 0.0]);
+    assert_eq!(*final_changes.borrow(), vec![0.0]);
+}
+
+#[test]
+fn slider_step_test() {
+    let slider = Slider::new("slider".to_string());
+    slider.borrow_mut().set_size((100, 20));
+    slider.borrow_mut().set_range((0.0, 1.0));
+    slider.borrow_mut().set_step(Some(0.25));
+
+    slider.borrow_mut().set_value(0.3);
+    assert_eq!(slider.borrow().value(), 0.25);
+    slider.borrow_mut().set_value(7.0);
+    assert_eq!(slider.borrow().value(), 1.0);
+
+    // arrow keys only move a focused slider
+    assert!(!slider.borrow().keyboard_event(Mod::empty(), Some(Scancode::Left), true, 0));
+    slider.borrow_mut().focus_event(true);
+    assert!(slider.borrow().keyboard_event(Mod::empty(), Some(Scancode::Left), true, 0));
+    assert_eq!(slider.borrow().value(), 0.75);
+    slider.borrow().keyboard_event(Mod::empty(), Some(Scancode::Right), true, 0);
+    slider.borrow().keyboard_event(Mod::empty(), Some(Scancode::Right), true, 0);
+    assert_eq!(slider.borrow().value(), 1.0);
+}
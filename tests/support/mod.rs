@@ -0,0 +1,271 @@
+// A widget for the tests that records the events it receives and otherwise
+// behaves like a plain WidgetObj. Every test file only uses a part of it.
+#![allow(dead_code)]
+
+use nanovg;
+use sdl2::keyboard::{Mod, Scancode};
+use sdl2::mouse::Mouse;
+use sdl2_sys::keycode::SDL_Keymod;
+use nanoguirustsdl::common::Cursor;
+use nanoguirustsdl::theme::Theme;
+use nanoguirustsdl::layout::Layout;
+use nanoguirustsdl::widget::{Widget, WidgetObj};
+use nanoguirustsdl::window::Window;
+use nanoguirustsdl::screen::Screen;
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+
+pub struct Probe {
+    widget: WidgetObj,
+    pub enter_events: RefCell<Vec<((u32, u32), bool)>>,
+    pub motion_events: RefCell<Vec<(u32, u32)>>,
+    pub button_events: RefCell<Vec<((u32, u32), bool)>>,
+    pub key_events: RefCell<Vec<Option<Scancode>>>,
+    pub text_events: RefCell<Vec<String>>,
+    // where the local (0, 0) was on the screen during the last draw
+    pub drawn_at: Cell<Option<(f32, f32)>>
+}
+
+impl Probe {
+    pub fn new(id: &str, pos: (u32, u32), size: (u32, u32)) -> Rc<RefCell<Probe>> {
+        let mut widget = WidgetObj::new(id.to_string());
+        widget.set_pos(pos);
+        widget.set_size(size);
+        Rc::new(RefCell::new(Probe { widget: widget, enter_events: RefCell::new(Vec::new()),
+                                   motion_events: RefCell::new(Vec::new()),
+                                   button_events: RefCell::new(Vec::new()),
+                                   key_events: RefCell::new(Vec::new()),
+                                   text_events: RefCell::new(Vec::new()),
+                                   drawn_at: Cell::new(None) }))
+    }
+
+    /// Returns and forgets the recorded mouse enter and leave events.
+    pub fn take_enter_events(&self) -> Vec<((u32, u32), bool)> {
+        self.enter_events.borrow_mut().drain(..).collect()
+    }
+
+    /// Returns and forgets the positions of the recorded mouse motion events.
+    pub fn take_motion_events(&self) -> Vec<(u32, u32)> {
+        self.motion_events.borrow_mut().drain(..).collect()
+    }
+}
+
+impl Widget for Probe {
+    fn parent(&self) -> Option<&Weak<RefCell<Widget>>> {
+        self.widget.parent()
+    }
+
+    unsafe fn set_parent(&mut self, parent: Option<Rc<RefCell<Widget>>>) {
+        self.widget.set_parent(parent)
+    }
+
+    fn children(&self) -> Vec<Rc<RefCell<Widget>>> {
+        self.widget.children()
+    }
+
+    unsafe fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Widget>>> {
+        self.widget.children_mut()
+    }
+
+    fn id(&self) -> String {
+        self.widget.id()
+    }
+
+    fn set_id(&mut self, id: String) {
+        self.widget.set_id(id)
+    }
+
+    fn pos(&self) -> (u32, u32) {
+        self.widget.pos()
+    }
+
+    fn set_pos(&mut self, p: (u32, u32)) {
+        self.widget.set_pos(p)
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.widget.size()
+    }
+
+    fn set_size(&mut self, s: (u32, u32)) {
+        self.widget.set_size(s)
+    }
+
+    fn fixed_size(&self) -> (u32, u32) {
+        self.widget.fixed_size()
+    }
+
+    fn set_fixed_size(&mut self, s: (u32, u32)) {
+        self.widget.set_fixed_size(s)
+    }
+
+    fn font_size(&self) -> u32 {
+        self.widget.font_size()
+    }
+
+    fn set_font_size(&mut self, s: Option<u32>) {
+        self.widget.set_font_size(s)
+    }
+
+    fn theme(&self) -> Option<&Rc<RefCell<Theme>>> {
+        self.widget.theme()
+    }
+
+    fn set_theme(&mut self, theme: Option<Rc<RefCell<Theme>>>) {
+        self.widget.set_theme(theme)
+    }
+
+    fn enabled(&self) -> bool {
+        self.widget.enabled()
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.widget.set_enabled(enabled)
+    }
+
+    fn tooltip(&self) -> String {
+        self.widget.tooltip()
+    }
+
+    fn set_tooltip(&mut self, tooltip: String) {
+        self.widget.set_tooltip(tooltip)
+    }
+
+    fn visible(&self) -> bool {
+        self.widget.visible()
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.widget.set_visible(visible)
+    }
+
+    fn focused(&self) -> bool {
+        self.widget.focused()
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.widget.set_focused(focused)
+    }
+
+    fn mouse_focus(&self) -> bool {
+        self.widget.mouse_focus()
+    }
+
+    fn layout(&self) -> Option<&Box<Layout>> {
+        self.widget.layout()
+    }
+
+    fn set_layout(&mut self, layout: Option<Box<Layout>>) {
+        self.widget.set_layout(layout)
+    }
+
+    fn cursor(&self) -> Cursor {
+        self.widget.cursor()
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) {
+        self.widget.set_cursor(cursor)
+    }
+
+    fn perform_layout(&self, nanovg_context: &nanovg::Context) {
+        self.widget.perform_layout(nanovg_context)
+    }
+
+    fn preferred_size(&self, nanovg_context: &nanovg::Context) -> (u32, u32) {
+        self.widget.preferred_size(nanovg_context)
+    }
+
+    fn draw(&self, nanovg_context: &nanovg::Context) {
+        let mut xform = nanovg::Transform::new();
+        let (mut x, mut y) = (0.0, 0.0);
+        nanovg_context.current_transform(&mut xform);
+        xform.transform_point(&mut x, &mut y, 0.0, 0.0);
+        self.drawn_at.set(Some((x, y)));
+        self.widget.draw(nanovg_context)
+    }
+
+    fn absolute_position(&self) -> (u32, u32) {
+        self.widget.absolute_position()
+    }
+
+    fn visible_recursive(&self) -> bool {
+        self.widget.visible_recursive()
+    }
+
+    fn enabled_recursive(&self) -> bool {
+        self.widget.enabled_recursive()
+    }
+
+    fn contains(&self, p: (u32, u32)) -> bool {
+        self.widget.contains(p)
+    }
+
+    fn scroll_offset(&self) -> (u32, u32) {
+        self.widget.scroll_offset()
+    }
+
+    fn clip_to_bounds(&self) -> bool {
+        self.widget.clip_to_bounds()
+    }
+
+    fn request_focus(&self) {
+        self.widget.request_focus()
+    }
+
+    fn accepts_text_input(&self) -> bool {
+        self.widget.accepts_text_input()
+    }
+
+    fn caret_rect(&self) -> Option<(f32, f32, f32, f32)> {
+        self.widget.caret_rect()
+    }
+
+    fn mouse_button_event(&self, p: (u32, u32), button: Mouse, down: bool, mods: SDL_Keymod) -> bool {
+        self.button_events.borrow_mut().push((p, down));
+        self.widget.mouse_button_event(p, button, down, mods)
+    }
+
+    fn mouse_motion_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.motion_events.borrow_mut().push(p);
+        self.widget.mouse_motion_event(p, rel, button, mods)
+    }
+
+    fn mouse_drag_event(&self, p: (u32, u32), rel: (i32, i32), button: Mouse, mods: SDL_Keymod) -> bool {
+        self.widget.mouse_drag_event(p, rel, button, mods)
+    }
+
+    fn mouse_enter_event(&mut self, p: (u32, u32), enter: bool) -> bool {
+        self.enter_events.borrow_mut().push((p, enter));
+        self.widget.mouse_enter_event(p, enter)
+    }
+
+    fn scroll_event(&self, p: (u32, u32), rel: (i32, i32)) -> bool {
+        self.widget.scroll_event(p, rel)
+    }
+
+    fn focus_event(&mut self, focused: bool) -> bool {
+        self.widget.focus_event(focused)
+    }
+
+    fn keyboard_event(&self, key: Mod, scancode: Option<Scancode>, pressed: bool, mods: SDL_Keymod) -> bool {
+        self.key_events.borrow_mut().push(scancode);
+        self.widget.keyboard_event(key, scancode, pressed, mods)
+    }
+
+    fn text_input_event(&self, text: &str) -> bool {
+        self.text_events.borrow_mut().push(text.to_string());
+        self.widget.text_input_event(text)
+    }
+
+    fn text_editing_event(&self, text: &str, start: i32, length: i32) -> bool {
+        self.widget.text_editing_event(text, start, length)
+    }
+
+    fn as_window(&self) -> Option<&Window> {
+        self.widget.as_window()
+    }
+
+    fn as_screen(&self) -> Option<&Screen> {
+        self.widget.as_screen()
+    }
+}
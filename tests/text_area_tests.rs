@@ -0,0 +1,171 @@
+extern crate nanoguirustsdl;
+extern crate nanovg;
+extern crate sdl2;
+
+use nanoguirustsdl::text_area::TextArea;
+use nanoguirustsdl::text_buffer::TextBuffer;
+use nanoguirustsdl::theme::Theme;
+use nanoguirustsdl::widget::Widget;
+use sdl2::keyboard::{Mod, Scancode, LCTRLMOD, LSHIFTMOD};
+use sdl2::mouse::Mouse;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+fn press(text_area: &Rc<RefCell<TextArea>>, scancode: Scancode, mods: u32) {
+    text_area.borrow().keyboard_event(Mod::empty(), Some(scancode), true, mods);
+}
+
+#[test]
+fn text_area_editing_test() {
+    let text_area = TextArea::new("area".to_string(), "first\nsecond\r\nthird".to_string());
+    assert_eq!(text_area.borrow().line_count(), 3);
+    assert_eq!(text_area.borrow().line(1), Some("second".to_string()));
+
+    text_area.borrow_mut().focus_event(true);
+    text_area.borrow_mut().set_cursor_pos((1, 3));
+    press(&text_area, Scancode::Return, 0);
+    assert_eq!(text_area.borrow().text(), "first\nsec\nond\nthird");
+    assert_eq!(text_area.borrow().cursor_pos(), (2, 0));
+
+    press(&text_area, Scancode::Backspace, 0);
+    assert_eq!(text_area.borrow().cursor_pos(), (1, 3));
+    press(&text_area, Scancode::Up, LSHIFTMOD.bits());
+    assert_eq!(text_area.borrow().selected_text(), Some("st\nsec".to_string()));
+
+    text_area.borrow().text_input_event("x\ny");
+    assert_eq!(text_area.borrow().text(), "firx\nyond\nthird");
+    assert_eq!(text_area.borrow().cursor_pos(), (1, 1));
+
+    press(&text_area, Scancode::Z, LCTRLMOD.bits());
+    assert_eq!(text_area.borrow().text(), "first\nsecond\nthird");
+    press(&text_area, Scancode::Z, LCTRLMOD.bits());
+    press(&text_area, Scancode::Z, LCTRLMOD.bits());
+    assert_eq!(text_area.borrow().text(), "first\nsecond\nthird");
+    assert_eq!(text_area.borrow().cursor_pos(), (1, 3));
+
+    press(&text_area, Scancode::Y, LCTRLMOD.bits());
+    assert_eq!(text_area.borrow().text(), "first\nsec\nond\nthird");
+}
+
+#[test]
+fn text_area_navigation_test() {
+    let text_area = TextArea::new("area".to_string(), "one two\n\nthree".to_string());
+    text_area.borrow_mut().focus_event(true);
+
+    press(&text_area, Scancode::End, LCTRLMOD.bits());
+    assert_eq!(text_area.borrow().cursor_pos(), (2, 5));
+    press(&text_area, Scancode::Up, 0);
+    assert_eq!(text_area.borrow().cursor_pos(), (1, 0));
+    press(&text_area, Scancode::Left, 0);
+    assert_eq!(text_area.borrow().cursor_pos(), (0, 7));
+    press(&text_area, Scancode::Left, LCTRLMOD.bits());
+    assert_eq!(text_area.borrow().cursor_pos(), (0, 4));
+    press(&text_area, Scancode::Home, LSHIFTMOD.bits());
+    assert_eq!(text_area.borrow().selected_text(), Some("one ".to_string()));
+}
+
+#[test]
+fn text_area_change_and_append_test() {
+    let text_area = TextArea::new("area".to_string(), String::new());
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let changes_clone = changes.clone();
+    text_area.borrow_mut().set_on_change(Some(Box::new(move |x: &str| changes_clone.borrow_mut().push(x.to_string()))));
+
+    let log: Vec<String> = (0..20000).map(|x| format!("line {}", x)).collect();
+    text_area.borrow_mut().append_text(&log.join("\n"));
+    assert_eq!(text_area.borrow().line_count(), 20000);
+
+    text_area.borrow_mut().focus_event(true);
+    text_area.borrow_mut().focus_event(false);
+    assert!(changes.borrow().is_empty());
+
+    text_area.borrow_mut().focus_event(true);
+    text_area.borrow().text_input_event("!");
+    text_area.borrow_mut().focus_event(false);
+    assert_eq!(changes.borrow().len(), 1);
+    assert!(changes.borrow()[0].starts_with("!line 0\n"));
+
+    // read-only areas ignore typing
+    text_area.borrow_mut().set_editable(false);
+    text_area.borrow_mut().focus_event(true);
+    assert!(!text_area.borrow().text_input_event("?"));
+}
+
+#[test]
+fn text_area_mouse_selection_test() {
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    let lines: Vec<String> = (0..20).map(|x| format!("line {}", x)).collect();
+    let text_area = TextArea::new("area".to_string(), lines.join("\n"));
+    text_area.borrow_mut().set_theme(Some(Rc::new(RefCell::new(Theme::new_debug()))));
+    text_area.borrow_mut().set_font_size(Some(16));
+    text_area.borrow_mut().set_pos((0, 50));
+    text_area.borrow_mut().set_size((200, 100));
+    text_area.borrow_mut().focus_event(true);
+    text_area.borrow().draw(&vg);
+
+    // rows are 20px high and start below the 5px padding, 2 chars are 16px wide
+    assert!(text_area.borrow().mouse_button_event((5 + 16, 50 + 5 + 25), Mouse::Left, true, 0));
+    assert_eq!(text_area.borrow().cursor_pos(), (1, 2));
+
+    // dragging above the widget selects towards the start, not to the end
+    assert!(text_area.borrow().mouse_drag_event((5 + 16, 10), (0, -70), Mouse::Left, 0));
+    assert_eq!(text_area.borrow().cursor_pos(), (0, 2));
+    assert_eq!(text_area.borrow().selected_text(), Some("ne 0\nli".to_string()));
+    text_area.borrow().mouse_button_event((5 + 16, 10), Mouse::Left, false, 0);
+
+    // the top padding belongs to the first row, clicks below the view to the last drawn one
+    text_area.borrow().mouse_button_event((5 + 8, 50 + 2), Mouse::Left, true, 0);
+    assert_eq!(text_area.borrow().cursor_pos(), (0, 1));
+    text_area.borrow().mouse_button_event((5, 50 + 99), Mouse::Left, true, 0);
+    assert_eq!(text_area.borrow().cursor_pos(), (4, 0));
+
+    // below the end of a short text the click goes to its end
+    text_area.borrow_mut().set_text("ab\ncd".to_string());
+    text_area.borrow().draw(&vg);
+    text_area.borrow().mouse_button_event((5, 50 + 90), Mouse::Left, true, 0);
+    assert_eq!(text_area.borrow().cursor_pos(), (1, 2));
+}
+
+#[test]
+fn text_area_composition_test() {
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    let text_area = TextArea::new("area".to_string(), "first\nsecond".to_string());
+    text_area.borrow_mut().set_theme(Some(Rc::new(RefCell::new(Theme::new_debug()))));
+    text_area.borrow_mut().set_size((200, 100));
+    assert!(!text_area.borrow().text_editing_event("ka", 2, 0));
+
+    text_area.borrow_mut().focus_event(true);
+    text_area.borrow_mut().set_cursor_pos((1, 3));
+    assert!(text_area.borrow().text_editing_event("ka", 2, 0));
+    assert_eq!(text_area.borrow().composition(), "ka");
+    // the pre-edit text is only shown, the document does not change until it is committed
+    text_area.borrow().draw(&vg);
+    assert_eq!(text_area.borrow().text(), "first\nsecond");
+
+    assert!(text_area.borrow().text_input_event("\u{304b}"));
+    assert_eq!(text_area.borrow().composition(), "");
+    assert_eq!(text_area.borrow().text(), "first\nsec\u{304b}ond");
+
+    text_area.borrow().text_editing_event("a", 1, 0);
+    text_area.borrow_mut().focus_event(false);
+    assert_eq!(text_area.borrow().composition(), "");
+}
+
+#[test]
+fn text_area_word_movement_test() {
+    // within a line, word moves stop where they stop in a TextBox
+    let line = "  one two\t three  ";
+    let buffer = TextBuffer::new(line.to_string());
+    let text_area = TextArea::new("area".to_string(), line.to_string());
+    text_area.borrow_mut().focus_event(true);
+
+    for col in 1..line.chars().count() {
+        text_area.borrow_mut().set_cursor_pos((0, col));
+        press(&text_area, Scancode::Left, LCTRLMOD.bits());
+        assert_eq!(text_area.borrow().cursor_pos(), (0, buffer.word_start(col)));
+
+        text_area.borrow_mut().set_cursor_pos((0, col));
+        press(&text_area, Scancode::Right, LCTRLMOD.bits());
+        assert_eq!(text_area.borrow().cursor_pos(), (0, buffer.word_end(col)));
+    }
+}
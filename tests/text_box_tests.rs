@@ -0,0 +1,129 @@
+extern crate nanoguirustsdl;
+extern crate sdl2;
+
+use nanoguirustsdl::text_buffer::TextBuffer;
+use nanoguirustsdl::text_box::TextBox;
+use nanoguirustsdl::widget::Widget;
+use sdl2::keyboard::{Mod, Scancode, LCTRLMOD, LSHIFTMOD};
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[test]
+fn text_buffer_editing_test() {
+    let mut buffer = TextBuffer::new("héllo".to_string());
+    assert_eq!(buffer.cursor(), 5);
+
+    buffer.move_left(false, false);
+    buffer.move_left(true, false);
+    assert_eq!(buffer.selection(), Some((3, 4)));
+    assert_eq!(buffer.selected_text(), Some("l".to_string()));
+
+    buffer.insert("L");
+    assert_eq!(buffer.text(), "hélLo");
+    assert_eq!(buffer.cursor(), 4);
+    assert_eq!(buffer.selection(), None);
+
+    assert!(buffer.delete_backward(false));
+    assert_eq!(buffer.text(), "hélo");
+
+    assert!(buffer.undo());
+    assert!(buffer.undo());
+    assert_eq!(buffer.text(), "héllo");
+    assert!(!buffer.undo());
+
+    assert!(buffer.redo());
+    assert_eq!(buffer.text(), "hélLo");
+}
+
+#[test]
+fn text_buffer_word_jump_test() {
+    let mut buffer = TextBuffer::new("one two  three".to_string());
+
+    buffer.move_left(false, true);
+    assert_eq!(buffer.cursor(), 9);
+    buffer.move_left(false, true);
+    assert_eq!(buffer.cursor(), 4);
+    buffer.move_right(true, true);
+    assert_eq!(buffer.selection(), Some((4, 7)));
+
+    assert!(buffer.delete_backward(true));
+    assert_eq!(buffer.text(), "one   three");
+    buffer.set_cursor(0, false);
+    assert!(buffer.delete_forward(true));
+    assert_eq!(buffer.text(), "   three");
+}
+
+#[test]
+fn text_box_commit_test() {
+    let text_box = TextBox::new("box".to_string(), "12".to_string());
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let changes_clone = changes.clone();
+
+    text_box.borrow_mut().set_format(Some(Box::new(|x: &str| x.chars().all(|c| c.is_digit(10)))));
+    text_box.borrow_mut().set_on_change(Some(Box::new(move |x: &str| changes_clone.borrow_mut().push(x.to_string()))));
+    text_box.borrow_mut().focus_event(true);
+
+    assert!(text_box.borrow().text_input_event("3"));
+    assert_eq!(text_box.borrow().editing_value(), "123");
+    assert_eq!(text_box.borrow().value(), "12");
+
+    assert!(text_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Return), true, 0));
+    assert_eq!(text_box.borrow().value(), "123");
+    assert_eq!(*changes.borrow(), vec!["123".to_string()]);
+
+    // invalid text is highlighted and reverted when the focus moves away
+    text_box.borrow().text_input_event("x");
+    assert!(!text_box.borrow().valid());
+    text_box.borrow_mut().focus_event(false);
+    assert_eq!(text_box.borrow().value(), "123");
+    assert_eq!(text_box.borrow().editing_value(), "123");
+    assert!(text_box.borrow().valid());
+    assert_eq!(changes.borrow().len(), 1);
+}
+
+#[test]
+fn text_box_keyboard_test() {
+    let text_box = TextBox::new("box".to_string(), "abc".to_string());
+    text_box.borrow_mut().focus_event(true);
+
+    let text_box_borrow = text_box.borrow();
+    text_box_borrow.keyboard_event(Mod::empty(), Some(Scancode::Home), true, LSHIFTMOD.bits());
+    assert_eq!(text_box_borrow.selection(), Some((0, 3)));
+
+    text_box_borrow.keyboard_event(Mod::empty(), Some(Scancode::Backspace), true, 0);
+    assert_eq!(text_box_borrow.editing_value(), "");
+
+    text_box_borrow.keyboard_event(Mod::empty(), Some(Scancode::Z), true, LCTRLMOD.bits());
+    assert_eq!(text_box_borrow.editing_value(), "abc");
+
+    text_box_borrow.keyboard_event(Mod::empty(), Some(Scancode::Escape), true, 0);
+    assert_eq!(text_box_borrow.cursor_pos(), 3);
+
+    // read-only boxes can still move the caret but not edit
+    drop(text_box_borrow);
+    text_box.borrow_mut().set_editable(false);
+    assert!(!text_box.borrow().text_input_event("d"));
+    text_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Left), true, 0);
+    assert_eq!(text_box.borrow().cursor_pos(), 2);
+    text_box.borrow().keyboard_event(Mod::empty(), Some(Scancode::Backspace), true, 0);
+    assert_eq!(text_box.borrow().editing_value(), "abc");
+}
+
+#[test]
+fn text_box_text_events_test() {
+    let text_box = TextBox::new("text".to_string(), String::new());
+
+    // only a focused box takes text
+    assert!(!text_box.borrow().text_input_event("a"));
+    assert!(!text_box.borrow().text_editing_event("a", 1, 0));
+    text_box.borrow_mut().focus_event(true);
+
+    // a composition is shown, but not part of the value until it is committed
+    assert!(text_box.borrow().text_editing_event("k", 1, 0));
+    assert_eq!(text_box.borrow().editing_value(), "");
+
+    // whole UTF-8 strings are inserted at once, not one code point per event
+    assert!(text_box.borrow().text_input_event("\u{304b}\u{1F600}"));
+    assert_eq!(text_box.borrow().editing_value(), "\u{304b}\u{1F600}");
+    assert_eq!(text_box.borrow().cursor_pos(), 2);
+}
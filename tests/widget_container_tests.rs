@@ -0,0 +1,323 @@
+extern crate nanoguirustsdl;
+extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
+
+mod support;
+
+use nanoguirustsdl::widget::{Widget, WidgetObj};
+use nanoguirustsdl::widget_container::{push_child, insert_child_at, remove_child_by_id, child_index,
+                                       move_child_to_front, move_child_to_back, swap_children,
+                                       find_widget, find_widget_path, find_child_path, update_hover_path,
+                                       draw_origin, visible_rect};
+use nanoguirustsdl::window::Window;
+use nanoguirustsdl::label::Label;
+use nanoguirustsdl::scroll_panel::VScrollPanel;
+use support::Probe;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+fn child_ids(container: &Rc<RefCell<WidgetObj>>) -> Vec<String> {
+    container.borrow().children().iter().map(|x| x.borrow().id()).collect()
+}
+
+fn container_with_children(ids: &[&str]) -> Rc<RefCell<WidgetObj>> {
+    let container = Rc::new(RefCell::new(WidgetObj::new("container".to_string())));
+    for id in ids {
+        push_child(container.clone(), Rc::new(RefCell::new(WidgetObj::new(id.to_string()))));
+    }
+    container
+}
+
+#[test]
+fn remove_child_preserves_order_test() {
+    let container = container_with_children(&["one", "two", "three", "four"]);
+
+    remove_child_by_id(container.clone(), "two".to_string());
+    assert_eq!(child_ids(&container), vec!["one", "three", "four"]);
+}
+
+#[test]
+fn insert_child_at_test() {
+    let container = container_with_children(&["one", "two"]);
+    let widget_three = Rc::new(RefCell::new(WidgetObj::new("three".to_string())));
+    let widget_four = Rc::new(RefCell::new(WidgetObj::new("four".to_string())));
+
+    insert_child_at(container.clone(), 1, widget_three.clone());
+    assert_eq!(child_ids(&container), vec!["one", "three", "two"]);
+
+    insert_child_at(container.clone(), 100, widget_four.clone());
+    assert_eq!(child_ids(&container), vec!["one", "three", "two", "four"]);
+
+    // re-inserting an existing child moves it instead of duplicating it
+    insert_child_at(container.clone(), 0, widget_four.clone());
+    assert_eq!(child_ids(&container), vec!["four", "one", "three", "two"]);
+
+    let widget_three_borrowed = widget_three.borrow();
+    if let Some(ref parent) = widget_three_borrowed.parent() {
+        assert_eq!(parent.upgrade().unwrap().borrow().id(), "container".to_string());
+    } else {
+        panic!("parent should have value");
+    }
+}
+
+#[test]
+fn child_index_test() {
+    let container = container_with_children(&["one", "two", "three"]);
+
+    assert_eq!(child_index(container.clone(), "one".to_string()), Some(0));
+    assert_eq!(child_index(container.clone(), "three".to_string()), Some(2));
+    assert_eq!(child_index(container.clone(), "four".to_string()), None);
+}
+
+#[test]
+fn z_order_test() {
+    let container = container_with_children(&["one", "two", "three"]);
+
+    // the last child is drawn on top
+    move_child_to_front(container.clone(), "one".to_string());
+    assert_eq!(child_ids(&container), vec!["two", "three", "one"]);
+
+    move_child_to_back(container.clone(), "three".to_string());
+    assert_eq!(child_ids(&container), vec!["three", "two", "one"]);
+
+    swap_children(container.clone(), 0, 2);
+    assert_eq!(child_ids(&container), vec!["one", "two", "three"]);
+}
+
+#[test]
+#[should_panic]
+fn swap_children_out_of_bounds_test() {
+    let container = container_with_children(&["one"]);
+    swap_children(container.clone(), 0, 1);
+}
+
+fn positioned_widget(id: &str, pos: (u32, u32), size: (u32, u32)) -> Rc<RefCell<WidgetObj>> {
+    let widget = Rc::new(RefCell::new(WidgetObj::new(id.to_string())));
+    widget.borrow_mut().set_pos(pos);
+    widget.borrow_mut().set_size(size);
+    widget
+}
+
+fn path_ids(path: &Vec<Rc<RefCell<Widget>>>) -> Vec<String> {
+    path.iter().map(|x| x.borrow().id()).collect()
+}
+
+#[test]
+fn find_widget_top_most_test() {
+    let root = positioned_widget("root", (0, 0), (100, 100));
+    let bottom = positioned_widget("bottom", (10, 10), (50, 50));
+    let top = positioned_widget("top", (20, 20), (50, 50));
+    push_child(root.clone(), bottom.clone());
+    push_child(root.clone(), top.clone());
+
+    // overlapping region hits the last child, which is drawn on top
+    assert_eq!(find_widget(root.clone(), (30, 30)).unwrap().borrow().id(), "top".to_string());
+    assert_eq!(find_widget(root.clone(), (15, 15)).unwrap().borrow().id(), "bottom".to_string());
+    assert_eq!(find_widget(root.clone(), (90, 90)).unwrap().borrow().id(), "root".to_string());
+    assert!(find_widget(root.clone(), (150, 150)).is_none());
+
+    move_child_to_front(root.clone(), "bottom".to_string());
+    assert_eq!(find_widget(root.clone(), (30, 30)).unwrap().borrow().id(), "bottom".to_string());
+}
+
+#[test]
+fn find_widget_path_nested_test() {
+    let root = positioned_widget("root", (0, 0), (200, 200));
+    let window = positioned_widget("window", (50, 50), (100, 100));
+    let label = positioned_widget("label", (10, 10), (20, 20));
+    push_child(root.clone(), window.clone());
+    push_child(window.clone(), label.clone());
+
+    // children positions are relative to their parent
+    let path = find_widget_path(root.clone(), (65, 65));
+    assert_eq!(path_ids(&path), vec!["root", "window", "label"]);
+
+    let path = find_widget_path(root.clone(), (15, 15));
+    assert_eq!(path_ids(&path), vec!["root"]);
+}
+
+#[test]
+fn find_widget_clipping_test() {
+    let root = positioned_widget("root", (0, 0), (200, 200));
+    let panel = positioned_widget("panel", (0, 0), (50, 50));
+    let overflowing = positioned_widget("overflowing", (40, 40), (50, 50));
+    push_child(root.clone(), panel.clone());
+    push_child(panel.clone(), overflowing.clone());
+
+    assert_eq!(path_ids(&find_widget_path(root.clone(), (45, 45))), vec!["root", "panel", "overflowing"]);
+    // the part of the child outside its parent is clipped away
+    assert_eq!(path_ids(&find_widget_path(root.clone(), (70, 70))), vec!["root"]);
+}
+
+#[test]
+fn find_widget_invisible_and_disabled_test() {
+    let root = positioned_widget("root", (0, 0), (100, 100));
+    let bottom = positioned_widget("bottom", (0, 0), (50, 50));
+    let top = positioned_widget("top", (0, 0), (50, 50));
+    let inner = positioned_widget("inner", (0, 0), (10, 10));
+    push_child(root.clone(), bottom.clone());
+    push_child(root.clone(), top.clone());
+    push_child(top.clone(), inner.clone());
+
+    top.borrow_mut().set_visible(false);
+    assert_eq!(path_ids(&find_widget_path(root.clone(), (5, 5))), vec!["root", "bottom"]);
+
+    // disabled widgets still cover what is below them, but are never hit themselves
+    top.borrow_mut().set_visible(true);
+    top.borrow_mut().set_enabled(false);
+    assert_eq!(path_ids(&find_widget_path(root.clone(), (5, 5))), vec!["root"]);
+
+    top.borrow_mut().set_enabled(true);
+    inner.borrow_mut().set_enabled(false);
+    assert_eq!(path_ids(&find_widget_path(root.clone(), (5, 5))), vec!["root", "top"]);
+}
+
+#[test]
+fn hover_path_nested_test() {
+    let root = positioned_widget("root", (0, 0), (200, 200));
+    let window = Probe::new("window", (50, 50), (100, 100));
+    let label = Probe::new("label", (10, 10), (20, 20));
+    let other = Probe::new("other", (0, 0), (40, 40));
+    push_child(root.clone(), window.clone());
+    push_child(root.clone(), other.clone());
+    push_child(window.clone(), label.clone());
+
+    let mut hover_path: Vec<Rc<RefCell<Widget>>> = Vec::new();
+    let mut move_mouse = |p: (u32, u32)| {
+        let new_path = find_child_path(&*root.borrow(), p);
+        update_hover_path(&hover_path, &new_path, p);
+        hover_path = new_path;
+    };
+
+    move_mouse((55, 55));
+    assert!(window.borrow().mouse_focus());
+    assert!(!label.borrow().mouse_focus());
+    assert_eq!(window.borrow().take_enter_events(), vec![((55, 55), true)]);
+
+    // entering a nested widget keeps its parent hovered, and every widget gets the
+    // position in the coordinates of its parent
+    move_mouse((65, 65));
+    assert!(window.borrow().mouse_focus());
+    assert!(label.borrow().mouse_focus());
+    assert_eq!(window.borrow().take_enter_events(), vec![]);
+    assert_eq!(label.borrow().take_enter_events(), vec![((15, 15), true)]);
+
+    move_mouse((10, 10));
+    assert!(!window.borrow().mouse_focus());
+    assert!(!label.borrow().mouse_focus());
+    assert!(other.borrow().mouse_focus());
+    assert_eq!(label.borrow().take_enter_events(), vec![((0, 0), false)]);
+    assert_eq!(window.borrow().take_enter_events(), vec![((10, 10), false)]);
+    assert_eq!(other.borrow().take_enter_events(), vec![((10, 10), true)]);
+}
+
+#[test]
+fn hover_path_leave_window_test() {
+    let root = positioned_widget("root", (0, 0), (200, 200));
+    let window = positioned_widget("window", (50, 50), (100, 100));
+    let label = positioned_widget("label", (10, 10), (20, 20));
+    push_child(root.clone(), window.clone());
+    push_child(window.clone(), label.clone());
+
+    let hover_path = find_child_path(&*root.borrow(), (65, 65));
+    update_hover_path(&Vec::new(), &hover_path, (65, 65));
+    assert!(label.borrow().mouse_focus());
+
+    // leaving the window entirely clears the whole path
+    update_hover_path(&hover_path, &Vec::new(), (65, 65));
+    assert!(!window.borrow().mouse_focus());
+    assert!(!label.borrow().mouse_focus());
+}
+
+#[test]
+fn visible_rect_nested_test() {
+    let root = positioned_widget("root", (0, 0), (200, 200));
+    let window = positioned_widget("window", (50, 50), (100, 100));
+    let label = positioned_widget("label", (10, 10), (20, 20));
+    let overflowing = positioned_widget("overflowing", (80, 90), (40, 40));
+    push_child(root.clone(), window.clone());
+    push_child(window.clone(), label.clone());
+    push_child(window.clone(), overflowing.clone());
+
+    assert_eq!(visible_rect(&*root.borrow()), Some((0, 0, 200, 200)));
+    assert_eq!(visible_rect(&*label.borrow()), Some((60, 60, 20, 20)));
+    // cut down to the part inside the window
+    assert_eq!(visible_rect(&*overflowing.borrow()), Some((130, 140, 20, 10)));
+}
+
+#[test]
+fn visible_rect_outside_test() {
+    let root = positioned_widget("root", (0, 0), (200, 200));
+    let window = positioned_widget("window", (50, 50), (100, 100));
+    let outside = positioned_widget("outside", (100, 20), (30, 30));
+    let nested = positioned_widget("nested", (0, 0), (10, 10));
+    push_child(root.clone(), window.clone());
+    push_child(window.clone(), outside.clone());
+    push_child(outside.clone(), nested.clone());
+
+    assert_eq!(visible_rect(&*outside.borrow()), None);
+    // the children of a widget that is out of view are out of view as well
+    assert_eq!(visible_rect(&*nested.borrow()), None);
+
+    let empty = positioned_widget("empty", (10, 10), (0, 20));
+    push_child(window.clone(), empty.clone());
+    assert_eq!(visible_rect(&*empty.borrow()), None);
+}
+
+#[test]
+fn clip_to_bounds_test() {
+    let widget = WidgetObj::new("widget".to_string());
+    assert!(widget.clip_to_bounds());
+
+    // windows draw their drop shadow outside of their bounds
+    let window = Window::new("window".to_string(), "Window".to_string());
+    assert!(!window.borrow().clip_to_bounds());
+}
+
+#[test]
+fn draw_origin_nested_test() {
+    let root = positioned_widget("root", (0, 0), (400, 400));
+    let window = Window::new("window".to_string(), "Window".to_string());
+    let panel = positioned_widget("panel", (15, 40), (200, 100));
+    let label = Label::new("label".to_string(), "caption".to_string(), "sans".to_string(), None);
+    window.borrow_mut().set_pos((100, 50));
+    label.borrow_mut().set_pos((5, 8));
+    push_child(root.clone(), window.clone());
+    push_child(window.clone(), panel.clone());
+    push_child(panel.clone(), label.clone());
+
+    // every level of the draw traversal translates by the position of the child
+    assert_eq!(draw_origin(&*root.borrow()), (0, 0));
+    assert_eq!(draw_origin(&*window.borrow()), (100, 50));
+    assert_eq!(draw_origin(&*panel.borrow()), (115, 90));
+    assert_eq!(draw_origin(&*label.borrow()), (120, 98));
+    assert_eq!(label.borrow().absolute_position(), (120, 98));
+}
+
+#[test]
+fn draw_traversal_test() {
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    let root = positioned_widget("root", (0, 0), (400, 400));
+    let window = Probe::new("window", (100, 50), (250, 250));
+    let panel = VScrollPanel::new("panel".to_string());
+    let content = Probe::new("content", (0, 0), (80, 300));
+    let label = Probe::new("label", (5, 48), (20, 20));
+    panel.borrow_mut().set_pos((15, 40));
+    panel.borrow_mut().set_size((100, 100));
+    content.borrow_mut().set_fixed_size((80, 300));
+    push_child(root.clone(), window.clone());
+    push_child(window.clone(), panel.clone());
+    push_child(panel.clone(), content.clone());
+    push_child(content.clone(), label.clone());
+    panel.borrow().perform_layout(&vg);
+    panel.borrow_mut().set_scroll((0.0, 40.0));
+
+    // the transforms the traversal sets up put every widget at its draw origin
+    root.borrow().draw(&vg);
+    assert_eq!(window.borrow().drawn_at.get(), Some((100.0, 50.0)));
+    assert_eq!(content.borrow().drawn_at.get(), Some((115.0, 50.0)));
+    assert_eq!(label.borrow().drawn_at.get(), Some((120.0, 98.0)));
+    assert_eq!(draw_origin(&*content.borrow()), (115, 50));
+    assert_eq!(draw_origin(&*label.borrow()), (120, 98));
+}
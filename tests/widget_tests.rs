@@ -1,9 +1,18 @@
 extern crate nanoguirustsdl;
 extern crate nanovg;
+extern crate sdl2;
+extern crate sdl2_sys;
 
+mod support;
+
+use nanoguirustsdl::screen::Screen;
 use nanoguirustsdl::widget::{Widget, WidgetObj};
 use nanoguirustsdl::widget_container::{push_child, remove_child_by_child};
 use nanoguirustsdl::theme::Theme;
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod, Scancode};
+use sdl2::mouse::{Mouse, MouseState};
+use support::Probe;
 use std::rc::Rc;
 use std::cell::RefCell;
 
@@ -122,3 +131,132 @@ fn theme_test() {
         None => panic!("Theme should have value")
     };
 }
+
+#[test]
+fn enabled_recursive_test() {
+    let widget_one = Rc::new(RefCell::new(WidgetObj::new("one".to_string())));
+    let widget_two = Rc::new(RefCell::new(WidgetObj::new("two".to_string())));
+    let widget_three = Rc::new(RefCell::new(WidgetObj::new("three".to_string())));
+    push_child(widget_one.clone(), widget_two.clone());
+    push_child(widget_two.clone(), widget_three.clone());
+
+    assert!(widget_three.borrow().enabled_recursive());
+
+    widget_one.borrow_mut().set_enabled(false);
+    assert!(widget_three.borrow().enabled());
+    assert!(!widget_three.borrow().enabled_recursive());
+    assert!(!widget_two.borrow().enabled_recursive());
+
+    widget_one.borrow_mut().set_enabled(true);
+    widget_three.borrow_mut().set_enabled(false);
+    assert!(widget_two.borrow().enabled_recursive());
+    assert!(!widget_three.borrow().enabled_recursive());
+}
+
+#[test]
+fn motion_leaving_child_test() {
+    let root = Rc::new(RefCell::new(WidgetObj::new("root".to_string())));
+    root.borrow_mut().set_size((100, 100));
+    let child = Probe::new("child", (10, 10), (20, 20));
+    push_child(root.clone(), child.clone());
+
+    // a child the mouse just left still gets the motion, also when it moved
+    // only vertically or out of its left side
+    root.borrow().mouse_motion_event((15, 35), (0, 10), Mouse::Left, 0);
+    assert_eq!(child.borrow().take_motion_events(), vec![(15, 35)]);
+    root.borrow().mouse_motion_event((5, 15), (-10, 0), Mouse::Left, 0);
+    assert_eq!(child.borrow().take_motion_events(), vec![(5, 15)]);
+
+    root.borrow().mouse_motion_event((60, 60), (5, 5), Mouse::Left, 0);
+    assert_eq!(child.borrow().take_motion_events(), vec![]);
+}
+
+// A screen with a probe at (10, 10) that has a child probe filling its top-left
+// corner.
+fn screen_with_probes() -> (Rc<RefCell<Screen>>, Rc<RefCell<Probe>>, Rc<RefCell<Probe>>) {
+    let screen = Screen::new_debug("screen".to_string(), (100, 100));
+    let parent = Probe::new("parent", (10, 10), (50, 50));
+    let child = Probe::new("child", (0, 0), (20, 20));
+    push_child(screen.clone(), parent.clone());
+    push_child(parent.clone(), child.clone());
+    (screen, parent, child)
+}
+
+#[test]
+fn disabled_focus_test() {
+    let (screen, parent, child) = screen_with_probes();
+    parent.borrow_mut().set_enabled(false);
+
+    let focused: Rc<RefCell<Widget>> = child.clone();
+    Screen::set_focus(screen.clone(), Some(focused.clone()));
+    assert!(screen.borrow().focus_path().is_empty());
+    assert!(!child.borrow().focused());
+
+    parent.borrow_mut().set_enabled(true);
+    Screen::set_focus(screen.clone(), Some(focused));
+    assert_eq!(screen.borrow().focus_path().len(), 2);
+    assert!(child.borrow().focused());
+}
+
+#[test]
+fn disabled_input_test() {
+    let (screen, parent, child) = screen_with_probes();
+    let focused: Rc<RefCell<Widget>> = child.clone();
+    Screen::set_focus(screen.clone(), Some(focused));
+    parent.borrow_mut().set_enabled(false);
+
+    let click = Event::MouseButtonDown { timestamp: 0, window_id: 0, which: 0, mouse_btn: Mouse::Left, x: 15, y: 15 };
+    let key = Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(Keycode::A), scancode: Some(Scancode::A), keymod: Mod::empty(), repeat: false };
+    let text = Event::TextInput { timestamp: 0, window_id: 0, text: "a".to_string() };
+    for event in &[&click, &key, &text] {
+        assert!(!Screen::handle_event(screen.clone(), event));
+    }
+    for probe in &[&parent, &child] {
+        assert!(probe.borrow().button_events.borrow().is_empty());
+        assert!(probe.borrow().key_events.borrow().is_empty());
+        assert!(probe.borrow().text_events.borrow().is_empty());
+    }
+
+    // the same events reach the child once its parent is enabled again
+    parent.borrow_mut().set_enabled(true);
+    for event in &[&click, &key, &text] {
+        Screen::handle_event(screen.clone(), event);
+    }
+    assert_eq!(*child.borrow().button_events.borrow(), vec![((5, 5), true)]);
+    assert_eq!(*child.borrow().key_events.borrow(), vec![Some(Scancode::A)]);
+    assert_eq!(*child.borrow().text_events.borrow(), vec!["a".to_string()]);
+}
+
+#[test]
+fn disabled_draw_test() {
+    let (screen, parent, child) = screen_with_probes();
+    child.borrow_mut().set_tooltip("tooltip".to_string());
+    parent.borrow_mut().set_enabled(false);
+
+    // hovering a disabled widget does not schedule its tooltip
+    let motion = Event::MouseMotion { timestamp: 0, window_id: 0, which: 0, mousestate: MouseState::from_sdl_state(0), x: 15, y: 15, xrel: 0, yrel: 0 };
+    Screen::handle_event(screen.clone(), &motion);
+    assert!(parent.borrow().take_enter_events().is_empty());
+    assert_eq!(screen.borrow().redraw_timeout(), None);
+
+    // but the widgets are still drawn, grayed out by their own draw code
+    let vg: nanovg::Context = nanovg::Context::create_gl3(nanovg::ANTIALIAS | nanovg::STENCIL_STROKES);
+    screen.borrow().draw(&vg);
+    assert_eq!(parent.borrow().drawn_at.get(), Some((10.0, 10.0)));
+    assert_eq!(child.borrow().drawn_at.get(), Some((10.0, 10.0)));
+
+    parent.borrow_mut().set_enabled(true);
+    Screen::handle_event(screen.clone(), &motion);
+    assert_eq!(screen.borrow().redraw_timeout(), Some(500));
+}
+
+#[test]
+fn font_test() {
+    let widget = Rc::new(RefCell::new(WidgetObj::new("widget".to_string())));
+    assert_eq!(widget.borrow().font(), "sans");
+
+    let theme = Rc::new(RefCell::new(Theme::new_debug()));
+    theme.borrow_mut().set_standard_font("serif".to_string());
+    widget.borrow_mut().set_theme(Some(theme.clone()));
+    assert_eq!(widget.borrow().font(), "serif");
+}